@@ -0,0 +1,80 @@
+/// Units of durations with their length in seconds, from the longest.
+const DURATION_UNITS: [(&str, f64); 5] = [
+    ("y", 365f64 * 24f64 * 60f64 * 60f64),
+    ("d", 24f64 * 60f64 * 60f64),
+    ("h", 60f64 * 60f64),
+    ("m", 60f64),
+    ("s", 1f64),
+];
+
+/// Formats a duration with its two largest units, such as `3h 12m` or `45s`.
+///
+/// The duration is rounded half up to the smaller shown unit, carrying into the larger units, so `59.5` seconds is `1m`.
+pub fn format_duration(seconds: f64) -> String {
+
+    if !seconds.is_finite() { return "∞".to_string(); }
+
+    let sign = if seconds < 0f64 { "-" } else { "" };
+    let mut seconds = seconds.abs().round();
+
+    let Some(mut index) = find_unit(seconds) else { return "0s".to_string() };
+
+    if let Some((_, next_length)) = DURATION_UNITS.get(index + 1) {
+
+        seconds = (seconds / next_length).round() * next_length;
+        index = find_unit(seconds).unwrap_or(index);
+
+    }
+
+    let (unit, length) = DURATION_UNITS[index];
+    let count = (seconds / length).floor();
+    let mut result = format!("{sign}{count}{unit}");
+
+    if let Some((next_unit, next_length)) = DURATION_UNITS.get(index + 1) {
+
+        let next_count = ((seconds - count * length) / next_length).floor();
+        if next_count > 0f64 { result.push_str(&format!(" {next_count}{next_unit}")); }
+
+    }
+
+    result
+
+}
+
+/// Returns the index of the largest unit that fits into the seconds.
+fn find_unit(seconds: f64) -> Option<usize> {
+
+    DURATION_UNITS.iter().position(|(_, length)| seconds >= *length)
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::format_duration;
+
+    #[test]
+    fn shows_two_largest_units() {
+
+        assert_eq!(format_duration(45f64), "45s");
+        assert_eq!(format_duration(3f64 * 3600f64 + 12f64 * 60f64 + 5f64), "3h 12m");
+        assert_eq!(format_duration(2f64 * 86400f64), "2d");
+        assert_eq!(format_duration(-90f64), "-1m 30s");
+        assert_eq!(format_duration(f64::INFINITY), "∞");
+
+    }
+
+    #[test]
+    fn rounds_half_up_and_carries_into_larger_units() {
+
+        assert_eq!(format_duration(0.004), "0s");
+        assert_eq!(format_duration(0.5), "1s");
+        assert_eq!(format_duration(59.4), "59s");
+        assert_eq!(format_duration(59.5), "1m");
+        assert_eq!(format_duration(3600f64 + 29f64), "1h");
+        assert_eq!(format_duration(3600f64 + 30f64), "1h 1m");
+        assert_eq!(format_duration(86400f64 - 30f64), "1d");
+
+    }
+
+}
@@ -0,0 +1,64 @@
+/// Separators used for formatting numbers in a locale.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct NumberLocale {
+
+    /// Separator between the integer part and the fraction.
+    pub decimal_separator: char,
+    /// Separator between groups of thousands.
+    pub group_separator: char,
+
+}
+
+impl NumberLocale {
+
+    /// Creates separators for a locale such as `en` or `de-DE`.
+    ///
+    /// Unknown locales use English separators.
+    pub fn from_locale(locale: &str) -> Self {
+
+        let language = locale
+            .split(['-', '_'])
+            .next()
+            .unwrap_or(locale)
+            .to_ascii_lowercase();
+
+        match language.as_str() {
+            "de" | "es" | "it" | "pt" | "nl" | "id" | "tr" | "da" => Self { decimal_separator: ',', group_separator: '.' },
+            "fr" | "ru" | "uk" | "pl" | "cs" | "sv" | "fi" | "nb" => Self { decimal_separator: ',', group_separator: '\u{a0}' },
+            _ => Self::default(),
+        }
+
+    }
+
+}
+
+impl Default for NumberLocale {
+
+    fn default() -> Self {
+
+        Self {
+            decimal_separator: '.',
+            group_separator: ',',
+        }
+
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::NumberLocale;
+
+    #[test]
+    fn uses_separators_of_the_language() {
+
+        assert_eq!(NumberLocale::from_locale("en-US"), NumberLocale::default());
+        assert_eq!(NumberLocale::from_locale("de-DE"), NumberLocale { decimal_separator: ',', group_separator: '.' });
+        assert_eq!(NumberLocale::from_locale("FR_ca"), NumberLocale { decimal_separator: ',', group_separator: '\u{a0}' });
+        assert_eq!(NumberLocale::from_locale("xx"), NumberLocale::default());
+        assert_eq!(NumberLocale::from_locale(""), NumberLocale::default());
+
+    }
+
+}
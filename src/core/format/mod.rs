@@ -0,0 +1,7 @@
+mod duration;
+mod locale;
+mod number;
+
+pub use duration::format_duration;
+pub use locale::NumberLocale;
+pub use number::{format_number, format_rate, NumberNotation};
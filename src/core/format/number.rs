@@ -0,0 +1,282 @@
+use super::NumberLocale;
+use crate::core::number::{to_f64, to_scientific, Number};
+
+/// Suffixes of the short notation, one for each group of thousands.
+const SHORT_SUFFIXES: [&str; 12] = ["", "k", "M", "B", "T", "Qa", "Qi", "Sx", "Sp", "Oc", "No", "Dc"];
+
+/// Significant digits shown by the short, scientific and engineering notations.
+const SIGNIFICANT_DIGITS: i64 = 3;
+
+/// Decimals shown for values below a thousand.
+const SMALL_DECIMALS: usize = 2;
+
+/// Exponent from which the full notation shows digits beyond `f64` precision as zeros.
+const FULL_ROUNDED_EXPONENT: i64 = 15;
+
+/// Largest exponent shown by the full notation.
+const FULL_MAX_EXPONENT: i64 = 308;
+
+/// Notation used for formatting numbers.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum NumberNotation {
+    /// Suffixes for thousands, such as `1.23k` and `45.6M`.
+    ///
+    /// Falls back to the scientific notation when suffixes run out.
+    #[default]
+    Short,
+    /// Mantissa and exponent, such as `1.23e45`.
+    Scientific,
+    /// Mantissa and an exponent that is a multiple of 3, such as `123e42`.
+    Engineering,
+    /// Every digit with group separators, such as `1,234,567`.
+    ///
+    /// Falls back to the scientific notation for values beyond `f64`.
+    Full,
+}
+
+impl NumberNotation {
+
+    /// Converts a notation name into `NumberNotation`.
+    pub fn from_str_safe(notation: &str) -> Result<NumberNotation, String> {
+
+        match notation {
+            "short" => Ok(NumberNotation::Short),
+            "scientific" => Ok(NumberNotation::Scientific),
+            "engineering" => Ok(NumberNotation::Engineering),
+            "full" => Ok(NumberNotation::Full),
+            _ => Err(format!("wrong number notation '{notation}'")),
+        }
+
+    }
+
+}
+
+/// Formats a number.
+///
+/// Values below a thousand are shown with up to 2 decimals in every notation.
+/// Shown digits are rounded half up, carrying into the next suffix or exponent, so `999500` is `1M` and `1e6`.
+/// Negative values are formatted like positive values with a leading `-`, and values that round to zero never have a sign.
+pub fn format_number(value: Number, notation: NumberNotation, locale: &NumberLocale) -> String {
+
+    let (mantissa, exponent) = to_scientific(value);
+
+    if mantissa.is_nan() { return "NaN".to_string(); }
+    if mantissa.is_infinite() { return if mantissa < 0f64 { "-∞" } else { "∞" }.to_string(); }
+
+    let magnitude = if notation == NumberNotation::Full && exponent <= FULL_MAX_EXPONENT { format_full(to_f64(value).abs(), exponent, locale) }
+        else { format_magnitude(mantissa.abs(), exponent, notation, locale) };
+
+    if mantissa < 0f64 && magnitude != "0" { format!("-{magnitude}") }
+    else { magnitude }
+
+}
+
+/// Formats a rate per second, such as `+1.23k/s`.
+pub fn format_rate(value_per_second: Number, notation: NumberNotation, locale: &NumberLocale) -> String {
+
+    let value = format_number(value_per_second, notation, locale);
+
+    if value.starts_with('-') || value == "0" || value == "NaN" { format!("{value}/s") }
+    else { format!("+{value}/s") }
+
+}
+
+/// Formats a positive number split into a mantissa between 1 and 10 and an exponent.
+fn format_magnitude(mantissa: f64, exponent: i64, notation: NumberNotation, locale: &NumberLocale) -> String {
+
+    if mantissa == 0f64 { return "0".to_string(); }
+
+    if exponent < 3 {
+
+        let value = mantissa * pow10(exponent);
+        let rounded = round_decimals(value, SMALL_DECIMALS);
+
+        if rounded < 1000f64 { return format_decimal(rounded, SMALL_DECIMALS, locale); }
+
+        return format_magnitude(1f64, 3, notation, locale);
+
+    }
+
+    let (mantissa, exponent) = round_significant(mantissa, exponent);
+
+    match notation {
+        NumberNotation::Scientific | NumberNotation::Full => format!("{}e{exponent}", format_decimal(mantissa, (SIGNIFICANT_DIGITS - 1) as usize, locale)),
+        NumberNotation::Engineering | NumberNotation::Short => {
+
+            let group = exponent.div_euclid(3);
+            let shift = exponent - group * 3;
+            let digits = format_decimal(mantissa * 10f64.powi(shift as i32), (SIGNIFICANT_DIGITS - 1 - shift) as usize, locale);
+
+            if notation == NumberNotation::Engineering { return format!("{digits}e{}", group * 3); }
+
+            match SHORT_SUFFIXES.get(group as usize) {
+                Some(suffix) => format!("{digits}{suffix}"),
+                None => format_magnitude(mantissa, exponent, NumberNotation::Scientific, locale),
+            }
+
+        },
+    }
+
+}
+
+/// Formats every digit of a positive value that fits into `f64`.
+fn format_full(value: f64, exponent: i64, locale: &NumberLocale) -> String {
+
+    if exponent < FULL_ROUNDED_EXPONENT { return format_decimal(round_decimals(value, SMALL_DECIMALS), SMALL_DECIMALS, locale); }
+
+    // The shortest representation avoids showing the error of the binary representation as digits.
+    let representation = format!("{value:e}");
+    let (mantissa, exponent) = representation.split_once('e').unwrap_or((&representation, "0"));
+    let exponent = exponent.parse::<i64>().unwrap_or(0);
+    let mut digits = mantissa.replace('.', "");
+    digits.push_str(&"0".repeat((exponent + 1).saturating_sub(digits.len() as i64) as usize));
+
+    group_digits(&digits, locale)
+
+}
+
+/// Rounds a mantissa between 1 and 10 to the significant digits, keeping it between 1 and 10.
+fn round_significant(mantissa: f64, exponent: i64) -> (f64, i64) {
+
+    let rounded = round_decimals(mantissa, (SIGNIFICANT_DIGITS - 1) as usize);
+
+    if rounded >= 10f64 { (rounded / 10f64, exponent + 1) }
+    else { (rounded, exponent) }
+
+}
+
+/// Returns `10^exponent`, which is 0 or infinite beyond `f64`.
+fn pow10(exponent: i64) -> f64 {
+
+    10f64.powi(exponent.clamp(-400, 400) as i32)
+
+}
+
+/// Rounds a positive value half up to the given decimals.
+///
+/// The value is scaled in its shortest decimal representation, so `9.995` rounds up even though its binary value is slightly below.
+fn round_decimals(value: f64, decimals: usize) -> f64 {
+
+    let representation = format!("{value:e}");
+    let (mantissa, exponent) = representation.split_once('e').unwrap_or((&representation, "0"));
+    let exponent = exponent.parse::<i64>().unwrap_or(0) + decimals as i64;
+    let scaled = format!("{mantissa}e{exponent}").parse::<f64>().unwrap_or(value * 10f64.powi(decimals as i32));
+
+    scaled.round() / 10f64.powi(decimals as i32)
+
+}
+
+/// Formats a positive value with group separators and up to the given decimals, trailing zeros are removed.
+fn format_decimal(value: f64, decimals: usize, locale: &NumberLocale) -> String {
+
+    let digits = format!("{value:.decimals$}");
+    let (integer, fraction) = digits.split_once('.').unwrap_or((&digits, ""));
+    let fraction = fraction.trim_end_matches('0');
+
+    let mut result = group_digits(integer, locale);
+
+    if !fraction.is_empty() {
+
+        result.push(locale.decimal_separator);
+        result.push_str(fraction);
+
+    }
+
+    result
+
+}
+
+/// Inserts group separators between thousands of an integer.
+fn group_digits(integer: &str, locale: &NumberLocale) -> String {
+
+    let mut result = String::with_capacity(integer.len() + integer.len() / 3);
+    for (i, c) in integer.chars().enumerate() {
+
+        if i > 0 && (integer.len() - i).is_multiple_of(3) { result.push(locale.group_separator); }
+        result.push(c);
+
+    }
+
+    result
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{format_number, format_rate, NumberNotation};
+    use crate::core::format::NumberLocale;
+    use crate::core::number::to_number;
+
+    fn format(value: f64, notation: NumberNotation) -> String {
+
+        format_number(to_number(value), notation, &NumberLocale::default())
+
+    }
+
+    #[test]
+    fn small_values_are_rounded_half_up_to_two_decimals() {
+
+        assert_eq!(format(0f64, NumberNotation::Short), "0");
+        assert_eq!(format(0.004, NumberNotation::Short), "0");
+        assert_eq!(format(0.005, NumberNotation::Short), "0.01");
+        assert_eq!(format(1.125, NumberNotation::Scientific), "1.13");
+        assert_eq!(format(12.5, NumberNotation::Engineering), "12.5");
+        assert_eq!(format(999.994, NumberNotation::Short), "999.99");
+        assert_eq!(format(999.995, NumberNotation::Short), "1k");
+        assert_eq!(format(-0.004, NumberNotation::Short), "0");
+        assert_eq!(format(-1.5, NumberNotation::Short), "-1.5");
+
+    }
+
+    #[test]
+    fn short_notation_carries_into_the_next_suffix() {
+
+        assert_eq!(format(1234f64, NumberNotation::Short), "1.23k");
+        assert_eq!(format(45_600_000f64, NumberNotation::Short), "45.6M");
+        assert_eq!(format(999_499f64, NumberNotation::Short), "999k");
+        assert_eq!(format(999_500f64, NumberNotation::Short), "1M");
+        assert_eq!(format(1.5e40, NumberNotation::Short), "1.5e40");
+
+    }
+
+    #[test]
+    fn scientific_and_engineering_notations_carry_into_the_next_exponent() {
+
+        assert_eq!(format(999_500f64, NumberNotation::Scientific), "1e6");
+        assert_eq!(format(123_456f64, NumberNotation::Scientific), "1.23e5");
+        assert_eq!(format(123_456f64, NumberNotation::Engineering), "123e3");
+        assert_eq!(format(999_500f64, NumberNotation::Engineering), "1e6");
+
+    }
+
+    #[test]
+    fn full_notation_groups_digits() {
+
+        assert_eq!(format(1_234_567f64, NumberNotation::Full), "1,234,567");
+        assert_eq!(format(1234.565, NumberNotation::Full), "1,234.57");
+        assert_eq!(format(1e20, NumberNotation::Full), "100,000,000,000,000,000,000");
+        assert_eq!(format_number(to_number(1234.5), NumberNotation::Full, &NumberLocale::from_locale("de")), "1.234,5");
+
+    }
+
+    #[test]
+    fn special_values() {
+
+        assert_eq!(format(f64::INFINITY, NumberNotation::Short), "∞");
+        assert_eq!(format(f64::NEG_INFINITY, NumberNotation::Short), "-∞");
+
+    }
+
+    #[test]
+    fn rates_have_a_sign() {
+
+        let locale = NumberLocale::default();
+
+        assert_eq!(format_rate(to_number(1500f64), NumberNotation::Short, &locale), "+1.5k/s");
+        assert_eq!(format_rate(to_number(-2f64), NumberNotation::Short, &locale), "-2/s");
+        assert_eq!(format_rate(to_number(0.001), NumberNotation::Short, &locale), "0/s");
+
+    }
+
+}
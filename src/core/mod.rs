@@ -1,4 +1,30 @@
+pub mod format;
+pub mod number;
 mod thing;
 
+#[cfg(feature = "big_number")]
+pub use number::BigNumber;
+pub use number::Number;
+
+pub use thing::AffordabilityEntry;
+pub use thing::AffordabilityReport;
+pub use thing::BuildingActivationError;
+pub use thing::BuildingConstructionError;
+pub use thing::BuildingConversionError;
+pub use thing::BuildingDemolishError;
+pub use thing::BuildingProductionError;
+pub use thing::BuildingPurchaseError;
+pub use thing::BuildingWorkerError;
+pub use thing::ThingEvent;
 pub use thing::ThingManager;
 pub use thing::ThingManagerLoadError;
+pub use thing::GatherError;
+pub use thing::ModifierScaling;
+pub use thing::PrestigeError;
+pub use thing::PrestigeUpgradePurchaseError;
+pub use thing::RateWindow;
+pub use thing::RecipeCraftError;
+pub use thing::ThingSave;
+pub use thing::ThingSnapshot;
+pub use thing::TICKS_PER_SECOND;
+pub use thing::UpgradePurchaseError;
@@ -0,0 +1,646 @@
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::Sum;
+use std::str::FromStr;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// Difference of exponents above which the smaller number has no effect on addition.
+const MAX_EXPONENT_DIFFERENCE: i64 = 17;
+
+/// Number stored as `mantissa × 10^exponent`.
+///
+/// The mantissa is kept between 1 and 10 (or 0), so values far beyond `f64::MAX` stay meaningful.
+/// Methods mirror the `f64` methods used by the game, so code can use either type through `Number`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BigNumber {
+    mantissa: f64,
+    exponent: i64,
+}
+
+impl BigNumber {
+
+    /// Creates a normalized number.
+    pub fn new(mantissa: f64, exponent: i64) -> Self {
+
+        if mantissa == 0f64 || !mantissa.is_finite() {
+
+            return Self { mantissa, exponent: 0 };
+
+        }
+
+        let shift = mantissa.abs().log10().floor();
+        let mut mantissa = mantissa / 10f64.powi(shift as i32);
+        let mut shift = shift as i64;
+
+        // `log10` can be off by one near powers of 10.
+        if mantissa.abs() >= 10f64 { mantissa /= 10f64; shift += 1; }
+        else if mantissa.abs() < 1f64 { mantissa *= 10f64; shift -= 1; }
+
+        match exponent.checked_add(shift) {
+            Some(exponent) => Self { mantissa, exponent },
+            None => Self::saturated(mantissa, shift > 0),
+        }
+
+    }
+
+    /// Returns `MAX` with the sign of the mantissa when the exponent overflowed, or 0 when it underflowed.
+    fn saturated(mantissa: f64, overflow: bool) -> Self {
+
+        match (overflow, mantissa < 0f64) {
+            (true, false) => Self::MAX,
+            (true, true) => -Self::MAX,
+            (false, _) => Self { mantissa: 0f64, exponent: 0 },
+        }
+
+    }
+
+    /// Creates a number from the sign and `log10` of its absolute value, saturating beyond the exponent range.
+    fn from_log10(sign: f64, log: f64) -> Self {
+
+        let exponent = log.floor();
+
+        if exponent >= i64::MAX as f64 { return Self::saturated(sign, true); }
+        if exponent < i64::MIN as f64 { return Self::saturated(sign, false); }
+
+        Self::new(sign * 10f64.powf(log - exponent), exponent as i64)
+
+    }
+
+    /// Returns the mantissa.
+    pub fn mantissa(&self) -> f64 {
+
+        self.mantissa
+
+    }
+
+    /// Returns the exponent.
+    pub fn exponent(&self) -> i64 {
+
+        self.exponent
+
+    }
+
+    /// Largest finite number.
+    pub const MAX: BigNumber = BigNumber { mantissa: 9.999999999999998, exponent: i64::MAX };
+
+    /// Is the number NaN?
+    pub fn is_nan(&self) -> bool {
+
+        self.mantissa.is_nan()
+
+    }
+
+    /// Is the number neither infinite nor NaN?
+    pub fn is_finite(&self) -> bool {
+
+        self.mantissa.is_finite()
+
+    }
+
+    /// Returns `log10` of the absolute value.
+    pub fn log10(&self) -> f64 {
+
+        self.exponent as f64 + self.mantissa.abs().log10()
+
+    }
+
+    pub fn abs(self) -> Self {
+
+        Self { mantissa: self.mantissa.abs(), exponent: self.exponent }
+
+    }
+
+    pub fn max(self, other: impl Into<BigNumber>) -> Self {
+
+        let other = other.into();
+        if other > self { other } else { self }
+
+    }
+
+    pub fn min(self, other: impl Into<BigNumber>) -> Self {
+
+        let other = other.into();
+        if other < self { other } else { self }
+
+    }
+
+    pub fn clamp(self, min: impl Into<BigNumber>, max: impl Into<BigNumber>) -> Self {
+
+        self.max(min).min(max)
+
+    }
+
+    pub fn floor(self) -> Self {
+
+        if self.exponent >= MAX_EXPONENT_DIFFERENCE { self } else { Self::from(f64::from(self).floor()) }
+
+    }
+
+    pub fn ceil(self) -> Self {
+
+        if self.exponent >= MAX_EXPONENT_DIFFERENCE { self } else { Self::from(f64::from(self).ceil()) }
+
+    }
+
+    pub fn powi(self, n: i32) -> Self {
+
+        if n == 0 { return Self::from(1f64); }
+        if self.mantissa == 0f64 { return self; }
+
+        let sign = if self.mantissa < 0f64 && n % 2 != 0 { -1f64 } else { 1f64 };
+
+        Self::from_log10(sign, self.log10() * n as f64)
+
+    }
+
+    pub fn powf(self, n: f64) -> Self {
+
+        if n == 0f64 { return Self::from(1f64); }
+        if self.mantissa <= 0f64 { return Self::from(f64::from(self).powf(n)); }
+
+        Self::from_log10(1f64, self.log10() * n)
+
+    }
+
+    pub fn sqrt(self) -> Self {
+
+        if self.mantissa <= 0f64 { return Self::from(f64::from(self).sqrt()); }
+
+        Self::from_log10(1f64, self.log10() / 2f64)
+
+    }
+
+}
+
+impl Default for BigNumber {
+
+    fn default() -> Self {
+
+        Self::from(0f64)
+
+    }
+
+}
+
+impl From<f64> for BigNumber {
+
+    fn from(value: f64) -> Self {
+
+        Self::new(value, 0)
+
+    }
+
+}
+
+impl From<BigNumber> for f64 {
+
+    /// Converts to `f64`, which is infinite if the number is too big.
+    fn from(value: BigNumber) -> Self {
+
+        if value.exponent > f64::MAX_10_EXP as i64 + 1 { return value.mantissa * f64::INFINITY; }
+        if value.exponent < f64::MIN_10_EXP as i64 - 17 { return 0f64; }
+
+        value.mantissa * 10f64.powi(value.exponent as i32)
+
+    }
+
+}
+
+impl fmt::Display for BigNumber {
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+
+        write!(f, "{}e{}", self.mantissa, self.exponent)
+
+    }
+
+}
+
+impl PartialOrd for BigNumber {
+
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+
+        if self.mantissa.is_nan() || other.mantissa.is_nan() { return None; }
+
+        let sign = self.mantissa.signum().partial_cmp(&other.mantissa.signum())?;
+        if sign != Ordering::Equal || self.mantissa == 0f64 || other.mantissa == 0f64 {
+
+            return self.mantissa.partial_cmp(&other.mantissa);
+
+        }
+
+        let ordering = self.exponent
+            .cmp(&other.exponent)
+            .then(self.mantissa.abs().partial_cmp(&other.mantissa.abs())?);
+
+        Some(if self.mantissa < 0f64 { ordering.reverse() } else { ordering })
+
+    }
+
+}
+
+impl PartialEq<f64> for BigNumber {
+
+    fn eq(&self, other: &f64) -> bool {
+
+        *self == BigNumber::from(*other)
+
+    }
+
+}
+
+impl PartialOrd<f64> for BigNumber {
+
+    fn partial_cmp(&self, other: &f64) -> Option<Ordering> {
+
+        self.partial_cmp(&BigNumber::from(*other))
+
+    }
+
+}
+
+impl Neg for BigNumber {
+
+    type Output = Self;
+
+    fn neg(self) -> Self {
+
+        Self { mantissa: -self.mantissa, exponent: self.exponent }
+
+    }
+
+}
+
+impl Add for BigNumber {
+
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+
+        if self.mantissa == 0f64 { return other; }
+        if other.mantissa == 0f64 { return self; }
+
+        let (big, small) = if self.exponent >= other.exponent { (self, other) } else { (other, self) };
+        let difference = big.exponent.saturating_sub(small.exponent);
+        if difference > MAX_EXPONENT_DIFFERENCE { return big; }
+
+        Self::new(big.mantissa + small.mantissa / 10f64.powi(difference as i32), big.exponent)
+
+    }
+
+}
+
+impl Sub for BigNumber {
+
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+
+        self + -other
+
+    }
+
+}
+
+impl Mul for BigNumber {
+
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+
+        let mantissa = self.mantissa * other.mantissa;
+
+        match self.exponent.checked_add(other.exponent) {
+            Some(exponent) => Self::new(mantissa, exponent),
+            None => Self::saturated(mantissa, other.exponent > 0),
+        }
+
+    }
+
+}
+
+impl Div for BigNumber {
+
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+
+        let mantissa = self.mantissa / other.mantissa;
+
+        match self.exponent.checked_sub(other.exponent) {
+            Some(exponent) => Self::new(mantissa, exponent),
+            None => Self::saturated(mantissa, other.exponent < 0),
+        }
+
+    }
+
+}
+
+impl Sum for BigNumber {
+
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+
+        iter.fold(BigNumber::default(), |a, b| a + b)
+
+    }
+
+}
+
+macro_rules! impl_f64_operations {
+    ($($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident;)*) => {
+        $(
+            impl $trait<f64> for BigNumber {
+
+                type Output = BigNumber;
+
+                fn $method(self, other: f64) -> BigNumber {
+
+                    self.$method(BigNumber::from(other))
+
+                }
+
+            }
+
+            impl $trait<BigNumber> for f64 {
+
+                type Output = BigNumber;
+
+                fn $method(self, other: BigNumber) -> BigNumber {
+
+                    BigNumber::from(self).$method(other)
+
+                }
+
+            }
+
+            impl $assign_trait for BigNumber {
+
+                fn $assign_method(&mut self, other: BigNumber) {
+
+                    *self = (*self).$method(other);
+
+                }
+
+            }
+
+            impl $assign_trait<f64> for BigNumber {
+
+                fn $assign_method(&mut self, other: f64) {
+
+                    *self = (*self).$method(BigNumber::from(other));
+
+                }
+
+            }
+        )*
+    };
+}
+
+impl_f64_operations! {
+    Add, add, AddAssign, add_assign;
+    Sub, sub, SubAssign, sub_assign;
+    Mul, mul, MulAssign, mul_assign;
+    Div, div, DivAssign, div_assign;
+}
+
+impl FromStr for BigNumber {
+
+    type Err = String;
+
+    /// Parses a `"<mantissa>e<exponent>"` string, the exponent is optional.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+
+        let (mantissa, exponent) = value
+            .split_once(['e', 'E'])
+            .unwrap_or((value, "0"));
+
+        let mantissa = mantissa.trim().parse::<f64>().map_err(|e| format!("wrong mantissa '{mantissa}', {e}"))?;
+        let exponent = exponent.trim().parse::<i64>().map_err(|e| format!("wrong exponent '{exponent}', {e}"))?;
+
+        Ok(BigNumber::new(mantissa, exponent))
+
+    }
+
+}
+
+impl Serialize for BigNumber {
+
+    /// Serializes as a number if it fits in `f64`, otherwise as a `"<mantissa>e<exponent>"` string.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+
+        let value = f64::from(*self);
+
+        if value.is_finite() { serializer.serialize_f64(value) }
+        else { serializer.serialize_str(&self.to_string()) }
+
+    }
+
+}
+
+impl<'de> Deserialize<'de> for BigNumber {
+
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+
+        deserializer.deserialize_any(BigNumberVisitor)
+
+    }
+
+}
+
+struct BigNumberVisitor;
+
+impl Visitor<'_> for BigNumberVisitor {
+
+    type Value = BigNumber;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+
+        formatter.write_str("a number or a \"<mantissa>e<exponent>\" string")
+
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<BigNumber, E> {
+
+        Ok(BigNumber::from(value))
+
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<BigNumber, E> {
+
+        Ok(BigNumber::from(value as f64))
+
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<BigNumber, E> {
+
+        Ok(BigNumber::from(value as f64))
+
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<BigNumber, E> {
+
+        value.parse::<BigNumber>().map_err(E::custom)
+
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::BigNumber;
+
+    /// Asserts that the number has the mantissa, within rounding error, and the exponent.
+    fn assert_number(number: BigNumber, mantissa: f64, exponent: i64) {
+
+        assert!((number.mantissa() - mantissa).abs() < 1e-9, "{number} is not {mantissa}e{exponent}");
+        assert_eq!(number.exponent(), exponent, "{number} is not {mantissa}e{exponent}");
+
+    }
+
+    #[test]
+    fn new_normalizes_mantissa() {
+
+        assert_number(BigNumber::new(1234f64, 0), 1.234, 3);
+        assert_number(BigNumber::new(0.05, 2), 5f64, 0);
+        assert_number(BigNumber::new(-250f64, 10), -2.5, 12);
+        assert_number(BigNumber::new(10f64, 0), 1f64, 1);
+        assert_number(BigNumber::new(0f64, 7), 0f64, 0);
+
+    }
+
+    #[test]
+    fn arithmetic_keeps_numbers_normalized() {
+
+        let a = BigNumber::new(6f64, 300);
+        let b = BigNumber::new(5f64, 300);
+
+        assert_number(a + b, 1.1, 301);
+        assert_number(a - b, 1f64, 300);
+        assert_number(a * b, 3f64, 601);
+        assert_number(a / b, 1.2, 0);
+        assert_number(a * 2f64, 1.2, 301);
+        assert_number(BigNumber::new(2f64, 400).powi(3), 8f64, 1200);
+        assert_number(BigNumber::new(4f64, 500).sqrt(), 2f64, 250);
+
+    }
+
+    #[test]
+    fn addition_ignores_much_smaller_numbers() {
+
+        let big = BigNumber::new(1f64, 100);
+
+        assert_eq!(big + BigNumber::from(1f64), big);
+        assert_eq!(BigNumber::from(1f64) + big, big);
+
+    }
+
+    #[test]
+    fn ordering_compares_exponents_before_mantissas() {
+
+        assert!(BigNumber::new(1f64, 10) > BigNumber::new(9f64, 9));
+        assert!(BigNumber::new(2f64, 10) > BigNumber::new(1f64, 10));
+        assert!(BigNumber::new(-1f64, 10) < BigNumber::new(-9f64, 9));
+        assert!(BigNumber::new(-1f64, 10) < BigNumber::default());
+        assert!(BigNumber::default() < BigNumber::new(1f64, -10));
+        assert!(BigNumber::new(1f64, 400) > f64::MAX);
+
+    }
+
+    #[test]
+    fn powf_keeps_large_exponents() {
+
+        assert_number(BigNumber::new(1f64, 1000).powf(0.5), 1f64, 500);
+        assert_number(BigNumber::new(8f64, 600).powf(1f64 / 3f64), 2f64, 200);
+        assert_number(BigNumber::new(5f64, 10).powf(0f64), 1f64, 0);
+        assert!(BigNumber::from(-4f64).powf(0.5).is_nan());
+        assert!(!BigNumber::MAX.is_nan() && BigNumber::MAX.is_finite());
+
+    }
+
+    #[test]
+    fn negatives_and_zero() {
+
+        let zero = BigNumber::default();
+        let three = BigNumber::from(3f64);
+
+        assert_number(zero - three, -3f64, 0);
+        assert_number(-three + three, 0f64, 0);
+        assert_number((zero - three).abs(), 3f64, 0);
+        assert_number(zero * BigNumber::new(5f64, 1000), 0f64, 0);
+        assert_number(BigNumber::from(-2f64).powi(3), -8f64, 0);
+        assert_eq!(f64::from(zero), 0f64);
+
+    }
+
+    #[test]
+    fn converts_to_f64() {
+
+        assert_eq!(f64::from(BigNumber::from(1.5)), 1.5);
+        assert_eq!(f64::from(BigNumber::new(1f64, 400)), f64::INFINITY);
+        assert_eq!(f64::from(BigNumber::new(-1f64, 400)), f64::NEG_INFINITY);
+        assert_eq!(f64::from(BigNumber::new(1f64, -400)), 0f64);
+
+    }
+
+    #[test]
+    fn parses_from_str() {
+
+        assert_number("1.5e400".parse::<BigNumber>().unwrap(), 1.5, 400);
+        assert_number("25E-3".parse::<BigNumber>().unwrap(), 2.5, -2);
+        assert_number("-42".parse::<BigNumber>().unwrap(), -4.2, 1);
+        assert!("abc".parse::<BigNumber>().is_err());
+        assert!("1e1.5".parse::<BigNumber>().is_err());
+
+    }
+
+    #[test]
+    fn serde_round_trip() {
+
+        let small = BigNumber::from(12.5);
+        assert_eq!(serde_json::to_string(&small).unwrap(), "12.5");
+        assert_eq!(serde_json::from_str::<BigNumber>("12.5").unwrap(), small);
+
+        let big = BigNumber::new(3f64, 500);
+        let json = serde_json::to_string(&big).unwrap();
+        assert_eq!(json, r#""3e500""#);
+        assert_eq!(serde_json::from_str::<BigNumber>(&json).unwrap(), big);
+
+        assert_number(serde_json::from_str::<BigNumber>("7").unwrap(), 7f64, 0);
+
+    }
+
+    #[test]
+    fn sums_numbers() {
+
+        let numbers = [BigNumber::new(5f64, 300), BigNumber::new(5f64, 300), BigNumber::from(1f64)];
+
+        assert_number(numbers.into_iter().sum(), 1f64, 301);
+        assert_number(std::iter::empty::<BigNumber>().sum(), 0f64, 0);
+
+    }
+
+    #[test]
+    fn exponent_overflow_saturates_to_max() {
+
+        let max = BigNumber::MAX;
+
+        assert_eq!(max + BigNumber::from(0.5), max);
+        assert_eq!(max - BigNumber::from(0.5), max);
+        assert_eq!(max + BigNumber::new(-1f64, -30), max);
+        assert_eq!(max * BigNumber::from(10f64), max);
+        assert_eq!(max * BigNumber::new(5f64, 100), max);
+        assert_eq!(-max * BigNumber::from(10f64), -max);
+        assert_eq!(max / BigNumber::new(1f64, -5), max);
+        assert_eq!(max.powf(2f64), max);
+        assert_eq!(BigNumber::new(50f64, i64::MAX), max);
+
+        assert_number(max / BigNumber::from(10f64), 9.999999999999998, i64::MAX - 1);
+        assert_number(BigNumber::new(2f64, i64::MIN) / BigNumber::new(1f64, 5), 0f64, 0);
+        assert_number(BigNumber::new(1f64, i64::MIN) + BigNumber::new(1f64, i64::MAX), 1f64, i64::MAX);
+
+    }
+
+}
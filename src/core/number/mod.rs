@@ -0,0 +1,92 @@
+#[cfg(feature = "big_number")]
+mod big;
+
+#[cfg(feature = "big_number")]
+pub use big::BigNumber;
+
+/// Number used for counts, capacities, prices and modifiers.
+///
+/// This is `f64` unless the `big_number` feature is enabled.
+#[cfg(not(feature = "big_number"))]
+pub type Number = f64;
+
+/// Number used for counts, capacities, prices and modifiers.
+///
+/// This is `BigNumber` because the `big_number` feature is enabled.
+#[cfg(feature = "big_number")]
+pub type Number = BigNumber;
+
+/// Largest finite `Number`.
+#[cfg(not(feature = "big_number"))]
+pub const NUMBER_MAX: Number = f64::MAX;
+
+/// Largest finite `Number`.
+#[cfg(feature = "big_number")]
+pub const NUMBER_MAX: Number = BigNumber::MAX;
+
+/// Converts `f64` into `Number`.
+#[cfg(not(feature = "big_number"))]
+pub fn to_number(value: f64) -> Number {
+
+    value
+
+}
+
+/// Converts `f64` into `Number`.
+#[cfg(feature = "big_number")]
+pub fn to_number(value: f64) -> Number {
+
+    BigNumber::from(value)
+
+}
+
+/// Converts `Number` into `f64`, which is infinite if the number is too big for `f64`.
+#[cfg(not(feature = "big_number"))]
+pub fn to_f64(value: Number) -> f64 {
+
+    value
+
+}
+
+/// Converts `Number` into `f64`, which is infinite if the number is too big for `f64`.
+#[cfg(feature = "big_number")]
+pub fn to_f64(value: Number) -> f64 {
+
+    f64::from(value)
+
+}
+
+/// Splits `Number` into a mantissa between 1 and 10 (or 0) and a base 10 exponent.
+#[cfg(not(feature = "big_number"))]
+pub fn to_scientific(value: Number) -> (f64, i64) {
+
+    if value == 0f64 || !value.is_finite() { return (value, 0); }
+
+    let mut exponent = value.abs().log10().floor() as i64;
+    let mut mantissa = value / 10f64.powi(exponent as i32);
+
+    // `log10` can be off by one near powers of 10.
+    if mantissa.abs() >= 10f64 { mantissa /= 10f64; exponent += 1; }
+    else if mantissa.abs() < 1f64 { mantissa *= 10f64; exponent -= 1; }
+
+    (mantissa, exponent)
+
+}
+
+/// Splits `Number` into a mantissa between 1 and 10 (or 0) and a base 10 exponent.
+#[cfg(feature = "big_number")]
+pub fn to_scientific(value: Number) -> (f64, i64) {
+
+    (value.mantissa(), value.exponent())
+
+}
+
+/// Clamps `Number` into a finite number, NaN becomes 0 and infinities become the largest finite numbers.
+pub fn to_finite(value: Number) -> Number {
+
+    if value.is_nan() { to_number(0f64) }
+    else if value.is_finite() { value }
+    else if value > 0f64 { NUMBER_MAX }
+    else { -NUMBER_MAX }
+
+}
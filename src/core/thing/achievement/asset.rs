@@ -0,0 +1,94 @@
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct AchievementAsset {
+
+    /// Name of the achievement.
+    pub name: String,
+    /// Category of the achievement.
+    pub category: String,
+    /// Description of the achievement.
+    #[serde(default)]
+    pub description: String,
+    /// Is the description hidden until the achievement is unlocked?
+    #[serde(default)]
+    pub hidden: bool,
+
+    /// Conditions that unlock the achievement once all of them are met.
+    pub conditions: Vec<AchievementCondition>,
+    /// Permanent modifiers that the unlocked achievement provides.
+    #[serde(default)]
+    pub modifiers: Vec<AchievementModifier>,
+
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AchievementCondition {
+
+    /// The resource count has reached the value.
+    ResourceCount {
+        /// Resource name.
+        name: String,
+        /// Required count.
+        count: f64,
+    },
+    /// The building count has reached the value.
+    BuildingCount {
+        /// Building name.
+        name: String,
+        /// Required count.
+        count: i32,
+    },
+    /// The upgrade level has reached the value.
+    Upgrade {
+        /// Upgrade name.
+        name: String,
+        /// Required level.
+        #[serde(default = "default_upgrade_count")]
+        count: i32,
+    },
+    /// The lifetime statistic has reached the value, such as `building.farm.purchased`.
+    Statistic {
+        /// Statistic name.
+        name: String,
+        /// Required value.
+        value: f64,
+    },
+    /// The event has happened the count of times.
+    Event {
+        /// Event type, such as `gathered`.
+        event: String,
+        /// Name of the thing the event happens to, any thing if it is not set.
+        #[serde(default)]
+        name: Option<String>,
+        /// Required count.
+        #[serde(default = "default_event_count")]
+        count: f64,
+    },
+
+}
+
+fn default_upgrade_count() -> i32 {
+
+    1
+
+}
+
+fn default_event_count() -> f64 {
+
+    1f64
+
+}
+
+#[derive(Deserialize)]
+pub struct AchievementModifier {
+
+    /// Name of the modifier
+    pub name: String,
+    /// Generated value of the modifier.
+    pub value: f64,
+    /// Calculation method of the modifier.
+    pub calculation: String,
+
+}
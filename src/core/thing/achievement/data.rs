@@ -0,0 +1,51 @@
+use crate::core::thing::achievement::AchievementAsset;
+
+/// Achievement
+pub struct Achievement {
+
+    /// Achievement's asset.
+    asset: AchievementAsset,
+
+    /// Is the achievement unlocked?
+    is_unlocked: bool,
+
+}
+
+impl From<AchievementAsset> for Achievement {
+
+    /// Creates an achievement object from an asset.
+    fn from(asset: AchievementAsset) -> Self {
+
+        Self {
+            asset,
+            is_unlocked: false,
+        }
+
+    }
+
+}
+
+impl Achievement {
+
+    /// Returns the achievement's asset.
+    pub fn asset(&self) -> &AchievementAsset {
+
+        &self.asset
+
+    }
+
+    /// Is the achievement unlocked?
+    pub fn is_unlocked(&self) -> bool {
+
+        self.is_unlocked
+
+    }
+
+    /// Unlocks the achievement.
+    pub fn unlock(&mut self) {
+
+        self.is_unlocked = true;
+
+    }
+
+}
@@ -0,0 +1,377 @@
+use crate::core::thing::achievement::{Achievement, AchievementAsset, AchievementCondition, AchievementSave, AchievementSnapshot};
+use crate::core::thing::building::BuildingManager;
+use crate::core::thing::modifier::{ModifierCalculationMethod, ModifierEntry, ModifierStorage};
+use crate::core::thing::resource::ResourceManager;
+use crate::core::thing::statistics::LifetimeStatistics;
+use crate::core::thing::upgrade::UpgradeManager;
+use crate::core::number::to_number;
+use std::collections::HashMap;
+
+/// Achievement manager.
+pub struct AchievementManager {
+
+    /// Achievements.
+    achievements: HashMap<String, Achievement>,
+    /// Names of locked achievements, sorted by name.
+    ///
+    /// Only these are checked every tick.
+    locked: Vec<String>,
+
+    /// Calculated modifiers of unlocked achievements.
+    calculated_modifiers: ModifierStorage,
+
+}
+
+impl AchievementManager {
+
+    /// Creates a new achievement manager.
+    pub fn new() -> Self {
+
+        Self {
+            achievements: HashMap::new(),
+            locked: Vec::new(),
+            calculated_modifiers: ModifierStorage::new(),
+        }
+
+    }
+
+}
+
+/// Implementations related to loading and registering achievements.
+impl AchievementManager {
+
+    /// Loads achievement from string.
+    ///
+    /// # Params
+    ///
+    /// - `achievement_asset_str`: JSON string of achievement asset.
+    pub fn load_from_str(&mut self, achievement_asset_str: &str) -> serde_json::Result<()> {
+
+        let result = serde_json::from_str(achievement_asset_str)?;
+        self.load_from_asset(result);
+        Ok(())
+
+    }
+
+    /// Loads achievement from asset.
+    pub fn load_from_asset(&mut self, achievement_asset: AchievementAsset) {
+
+        let achievement = Achievement::from(achievement_asset);
+        self.add(achievement);
+
+    }
+
+    /// Adds a new achievement.
+    pub fn add(&mut self, achievement: Achievement) {
+
+        self.achievements.insert(achievement.asset().name.clone(), achievement);
+        self.update_locked();
+
+    }
+
+    /// Refreshes names of locked achievements and modifiers of unlocked achievements.
+    fn update_locked(&mut self) {
+
+        self.locked = self.achievements
+            .values()
+            .filter(|a| !a.is_unlocked())
+            .map(|a| a.asset().name.clone())
+            .collect();
+
+        self.locked.sort();
+
+        self.calculated_modifiers.clear();
+
+        for achievement in self.achievements.values().filter(|a| a.is_unlocked()) {
+
+            for modifier in achievement.asset().modifiers.iter() {
+
+                self.calculated_modifiers.add(ModifierEntry::new(modifier.name.clone(), to_number(modifier.value), ModifierCalculationMethod::from_str(modifier.calculation.as_str())));
+
+            }
+
+        }
+
+    }
+
+}
+
+/// Implementations related to unlocking achievements.
+impl AchievementManager {
+
+    /// Unlocks achievements whose conditions are all met, and returns their names.
+    ///
+    /// Only locked achievements are checked, and modifiers are only recalculated when something was unlocked.
+    pub fn update_unlocks(&mut self, resource_manager: &ResourceManager, building_manager: &BuildingManager, upgrade_manager: &UpgradeManager, statistics: &LifetimeStatistics) -> Vec<String> {
+
+        let unlocked = self.locked
+            .iter()
+            .filter(|name| {
+
+                self.achievements[*name]
+                    .asset()
+                    .conditions
+                    .iter()
+                    .all(|c| Self::is_met(c, resource_manager, building_manager, upgrade_manager, statistics))
+
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if unlocked.is_empty() { return unlocked; }
+
+        for name in unlocked.iter() {
+
+            if let Some(achievement) = self.achievements.get_mut(name) { achievement.unlock(); }
+
+        }
+
+        self.update_locked();
+        unlocked
+
+    }
+
+    /// Is the condition met?
+    fn is_met(condition: &AchievementCondition, resource_manager: &ResourceManager, building_manager: &BuildingManager, upgrade_manager: &UpgradeManager, statistics: &LifetimeStatistics) -> bool {
+
+        match condition {
+            AchievementCondition::ResourceCount { name, count } => resource_manager.count(name) >= *count,
+            AchievementCondition::BuildingCount { name, count } => building_manager.get(name).is_some_and(|b| b.count() >= *count),
+            AchievementCondition::Upgrade { name, count } => upgrade_manager.count(name) >= *count,
+            AchievementCondition::Statistic { name, value } => statistics.value(name) >= *value,
+            AchievementCondition::Event { event, name, count } => {
+
+                let statistic = match name {
+                    Some(name) => format!("event.{event}.{name}"),
+                    None => format!("event.{event}"),
+                };
+
+                statistics.value(&statistic) >= *count
+
+            },
+        }
+
+    }
+
+    /// Returns permanent modifiers of unlocked achievements.
+    pub fn calculated_modifiers(&self) -> &ModifierStorage {
+
+        &self.calculated_modifiers
+
+    }
+
+}
+
+/// Implementations related to snapshots.
+impl AchievementManager {
+
+    /// Creates snapshots of achievements, sorted by name.
+    pub fn snapshot(&self) -> Vec<AchievementSnapshot> {
+
+        let mut snapshots = self.achievements
+            .values()
+            .map(AchievementSnapshot::from)
+            .collect::<Vec<_>>();
+
+        snapshots.sort_by(|a, b| a.name.cmp(&b.name));
+        snapshots
+
+    }
+
+}
+
+/// Implementations related to saves.
+impl AchievementManager {
+
+    /// Creates saves of achievements, sorted by name.
+    pub fn save(&self) -> Vec<AchievementSave> {
+
+        let mut saves = self.achievements
+            .values()
+            .map(AchievementSave::from)
+            .collect::<Vec<_>>();
+
+        saves.sort_by(|a, b| a.name.cmp(&b.name));
+        saves
+
+    }
+
+    /// Restores achievements from saves.
+    ///
+    /// Saves of achievements that do not exist are ignored.
+    pub fn load_save(&mut self, saves: &[AchievementSave]) {
+
+        for save in saves.iter() {
+
+            let Some(achievement) = self.achievements.get_mut(&save.name) else { continue };
+
+            if save.is_unlocked { achievement.unlock(); }
+
+        }
+
+        self.update_locked();
+
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::AchievementManager;
+    use crate::core::number::to_number;
+    use crate::core::thing::building::BuildingManager;
+    use crate::core::thing::event::ThingEvent;
+    use crate::core::thing::modifier::ModifierCalculationMethod;
+    use crate::core::thing::resource::ResourceManager;
+    use crate::core::thing::statistics::LifetimeStatistics;
+    use crate::core::thing::upgrade::{UpgradeManager, UpgradeSave};
+
+    /// Things the achievement conditions look at: wood, huts, the axe upgrade and statistics.
+    struct Things {
+        resource_manager: ResourceManager,
+        building_manager: BuildingManager,
+        upgrade_manager: UpgradeManager,
+        statistics: LifetimeStatistics,
+    }
+
+    impl Things {
+
+        fn new() -> Self {
+
+            let mut resource_manager = ResourceManager::new();
+            resource_manager.load_from_str(r#"{"name":"wood","category":"raw","base_capacity":100,"modifiers":[]}"#).unwrap();
+
+            let mut building_manager = BuildingManager::new();
+            building_manager.load_from_str(r#"{"name":"hut","category":"housing","prices":[],"price_multiplier":1,"size":0,"productions":[]}"#).unwrap();
+
+            let mut upgrade_manager = UpgradeManager::new();
+            upgrade_manager.load_from_str(r#"{"name":"axe","category":"tools","price":[],"price_multiplier":1,"unlocks":[],"modifiers":[]}"#).unwrap();
+
+            Self { resource_manager, building_manager, upgrade_manager, statistics: LifetimeStatistics::new() }
+
+        }
+
+        fn update(&self, achievement_manager: &mut AchievementManager) -> Vec<String> {
+
+            achievement_manager.update_unlocks(&self.resource_manager, &self.building_manager, &self.upgrade_manager, &self.statistics)
+
+        }
+
+    }
+
+    fn achievement(name: &str, conditions: &str) -> String {
+
+        format!(r#"{{"name":"{name}","category":"general","description":"Secret of {name}","hidden":true,"conditions":[{conditions}],"modifiers":[{{"name":"global.speed","value":0.1,"calculation":"additive"}}]}}"#)
+
+    }
+
+    fn constructed(name: &str) -> ThingEvent {
+
+        ThingEvent::BuildingConstructed { name: name.to_string() }
+
+    }
+
+    #[test]
+    fn every_condition_unlocks_its_achievement_once() {
+
+        let mut things = Things::new();
+        let mut achievement_manager = AchievementManager::new();
+
+        for (name, condition) in [
+            ("stockpile", r#"{"type":"resource_count","name":"wood","count":10}"#),
+            ("builder", r#"{"type":"building_count","name":"hut","count":2}"#),
+            ("smith", r#"{"type":"upgrade","name":"axe"}"#),
+            ("veteran", r#"{"type":"statistic","name":"ticks","value":5}"#),
+            ("constructor", r#"{"type":"event","event":"building_constructed","name":"hut","count":2}"#),
+            ("busy", r#"{"type":"event","event":"building_constructed","count":3}"#),
+        ] {
+
+            achievement_manager.load_from_str(&achievement(name, condition)).unwrap();
+
+        }
+
+        assert!(things.update(&mut achievement_manager).is_empty());
+
+        things.resource_manager.add_count("wood", to_number(10f64));
+        assert_eq!(things.update(&mut achievement_manager), vec!["stockpile"]);
+
+        things.building_manager.set_count("hut", 2);
+        assert_eq!(things.update(&mut achievement_manager), vec!["builder"]);
+
+        things.upgrade_manager.load_save(&[UpgradeSave { name: "axe".to_string(), count: 1, is_unlocked: true }]);
+        assert_eq!(things.update(&mut achievement_manager), vec!["smith"]);
+
+        things.statistics.add("ticks".to_string(), to_number(5f64));
+        assert_eq!(things.update(&mut achievement_manager), vec!["veteran"]);
+
+        things.statistics.record_event(&constructed("hut"));
+        things.statistics.record_event(&constructed("hut"));
+        assert_eq!(things.update(&mut achievement_manager), vec!["constructor"]);
+
+        things.statistics.record_event(&constructed("mill"));
+        assert_eq!(things.update(&mut achievement_manager), vec!["busy"]);
+
+        assert!(things.update(&mut achievement_manager).is_empty());
+
+    }
+
+    #[test]
+    fn every_condition_must_be_met() {
+
+        let mut things = Things::new();
+        let mut achievement_manager = AchievementManager::new();
+        achievement_manager.load_from_str(&achievement("settler", r#"{"type":"resource_count","name":"wood","count":10},{"type":"building_count","name":"hut","count":1}"#)).unwrap();
+
+        things.resource_manager.add_count("wood", to_number(10f64));
+        assert!(things.update(&mut achievement_manager).is_empty());
+
+        things.building_manager.set_count("hut", 1);
+        assert_eq!(things.update(&mut achievement_manager), vec!["settler"]);
+
+    }
+
+    #[test]
+    fn modifiers_and_descriptions_are_given_once_unlocked() {
+
+        let mut things = Things::new();
+        let mut achievement_manager = AchievementManager::new();
+        achievement_manager.load_from_str(&achievement("builder", r#"{"type":"building_count","name":"hut","count":1}"#)).unwrap();
+
+        let snapshot = &achievement_manager.snapshot()[0];
+        assert_eq!((snapshot.description.as_deref(), snapshot.is_hidden, snapshot.is_unlocked), (None, true, false));
+        assert_eq!(achievement_manager.calculated_modifiers().value("global.speed", ModifierCalculationMethod::Additive), 0f64);
+
+        things.building_manager.set_count("hut", 1);
+        things.update(&mut achievement_manager);
+
+        let snapshot = &achievement_manager.snapshot()[0];
+        assert_eq!((snapshot.description.as_deref(), snapshot.is_unlocked), (Some("Secret of builder"), true));
+        assert_eq!(achievement_manager.calculated_modifiers().value("global.speed", ModifierCalculationMethod::Additive), 0.1);
+
+    }
+
+    #[test]
+    fn unlocked_achievements_are_restored_from_a_save() {
+
+        let mut things = Things::new();
+        let mut achievement_manager = AchievementManager::new();
+        achievement_manager.load_from_str(&achievement("builder", r#"{"type":"building_count","name":"hut","count":1}"#)).unwrap();
+        achievement_manager.load_from_str(&achievement("smith", r#"{"type":"upgrade","name":"axe"}"#)).unwrap();
+
+        things.building_manager.set_count("hut", 1);
+        things.update(&mut achievement_manager);
+
+        let mut restored = AchievementManager::new();
+        restored.load_from_str(&achievement("builder", r#"{"type":"building_count","name":"hut","count":1}"#)).unwrap();
+        restored.load_from_str(&achievement("smith", r#"{"type":"upgrade","name":"axe"}"#)).unwrap();
+        restored.load_save(&achievement_manager.save());
+
+        let unlocked = restored.snapshot().iter().map(|snapshot| (snapshot.name.clone(), snapshot.is_unlocked)).collect::<Vec<_>>();
+        assert_eq!(unlocked, vec![("builder".to_string(), true), ("smith".to_string(), false)]);
+        assert_eq!(restored.calculated_modifiers().value("global.speed", ModifierCalculationMethod::Additive), 0.1);
+        assert!(things.update(&mut restored).is_empty());
+
+    }
+
+}
@@ -0,0 +1,11 @@
+mod asset;
+mod data;
+mod manager;
+mod save;
+mod snapshot;
+
+pub use asset::{AchievementAsset, AchievementCondition};
+pub use data::Achievement;
+pub use manager::AchievementManager;
+pub use save::AchievementSave;
+pub use snapshot::AchievementSnapshot;
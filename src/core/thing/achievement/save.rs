@@ -0,0 +1,27 @@
+use crate::core::thing::achievement::Achievement;
+use serde::{Deserialize, Serialize};
+
+/// Saved state of an achievement.
+#[derive(Serialize, Deserialize)]
+pub struct AchievementSave {
+
+    /// Name of the achievement.
+    pub name: String,
+    /// Is the achievement unlocked?
+    #[serde(default)]
+    pub is_unlocked: bool,
+
+}
+
+impl From<&Achievement> for AchievementSave {
+
+    fn from(achievement: &Achievement) -> Self {
+
+        Self {
+            name: achievement.asset().name.clone(),
+            is_unlocked: achievement.is_unlocked(),
+        }
+
+    }
+
+}
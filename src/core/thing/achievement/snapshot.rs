@@ -0,0 +1,38 @@
+use crate::core::thing::achievement::Achievement;
+use serde::Serialize;
+
+/// Snapshot of an achievement.
+#[derive(Serialize)]
+pub struct AchievementSnapshot {
+
+    /// Name of the achievement.
+    pub name: String,
+    /// Category of the achievement.
+    pub category: String,
+    /// Description of the achievement, `None` while a hidden achievement is locked.
+    pub description: Option<String>,
+
+    /// Is the description hidden until the achievement is unlocked?
+    pub is_hidden: bool,
+    /// Is the achievement unlocked?
+    pub is_unlocked: bool,
+
+}
+
+impl From<&Achievement> for AchievementSnapshot {
+
+    fn from(achievement: &Achievement) -> Self {
+
+        let is_masked = achievement.asset().hidden && !achievement.is_unlocked();
+
+        Self {
+            name: achievement.asset().name.clone(),
+            category: achievement.asset().category.clone(),
+            description: if is_masked { None } else { Some(achievement.asset().description.clone()) },
+            is_hidden: achievement.asset().hidden,
+            is_unlocked: achievement.is_unlocked(),
+        }
+
+    }
+
+}
@@ -0,0 +1,170 @@
+use crate::core::number::{to_f64, Number};
+use crate::core::thing::resource::{RateWindow, ResourceManager, ResourceStorage};
+use serde::Serialize;
+
+/// Affordability of a single price entry.
+#[derive(Serialize)]
+pub struct AffordabilityEntry {
+
+    /// Resource name.
+    pub name: String,
+    /// Price of the resource.
+    pub price: Number,
+    /// Current count of the resource.
+    pub count: Number,
+    /// Amount of the resource that is missing.
+    pub missing: Number,
+    /// Estimated ticks until the missing amount is produced at the current net rate.
+    ///
+    /// `None` if the resource is not growing or the price exceeds the capacity.
+    pub ticks: Option<f64>,
+    /// Is the price bigger than the resource's capacity?
+    ///
+    /// The price can never be paid without more storage.
+    pub exceeds_capacity: bool,
+
+}
+
+/// Affordability of a whole price.
+#[derive(Serialize)]
+pub struct AffordabilityReport {
+
+    /// Affordability of each price entry, sorted by resource name.
+    pub entries: Vec<AffordabilityEntry>,
+    /// Can the price be paid right now?
+    pub is_affordable: bool,
+    /// Estimated ticks until every price entry can be paid.
+    ///
+    /// `None` if any entry cannot be paid at the current rates.
+    pub ticks: Option<f64>,
+    /// Does any price entry exceed the resource's capacity?
+    pub exceeds_capacity: bool,
+
+}
+
+impl AffordabilityReport {
+
+    /// Creates a report for the price using current resources.
+    ///
+    /// # Params
+    ///
+    /// - `prices`: Calculated prices of the thing.
+    /// - `resource_manager`: Resource manager for resource counts, capacities and rates.
+    pub fn new(prices: &ResourceStorage, resource_manager: &ResourceManager) -> Self {
+
+        let mut entries = prices
+            .iter()
+            .map(|(name, price)| {
+
+                let count = resource_manager.count(name);
+                let capacity = resource_manager.capacity(name);
+                let rate = resource_manager.average_rate(name, RateWindow::Short);
+                let missing = (*price - count).max(0f64);
+                let exceeds_capacity = *price > capacity;
+
+                let ticks = if missing == 0f64 { Some(0f64) }
+                    else if exceeds_capacity || rate <= 0f64 { None }
+                    else { Some(to_f64(missing / rate).ceil()) };
+
+                AffordabilityEntry {
+                    name: name.clone(),
+                    price: *price,
+                    count,
+                    missing,
+                    ticks,
+                    exceeds_capacity,
+                }
+
+            })
+            .collect::<Vec<_>>();
+
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Self {
+            is_affordable: entries.iter().all(|e| e.missing == 0f64),
+            ticks: entries.iter().try_fold(0f64, |ticks, e| e.ticks.map(|t| ticks.max(t))),
+            exceeds_capacity: entries.iter().any(|e| e.exceeds_capacity),
+            entries,
+        }
+
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::AffordabilityReport;
+    use crate::core::number::to_number;
+    use crate::core::thing::modifier::ModifierStorage;
+    use crate::core::thing::resource::{ResourceManager, ResourceStorage};
+
+    /// Creates wood with capacity 100 and count 10 that changed by the rate in the last tick.
+    fn resource_manager(rate: f64) -> ResourceManager {
+
+        let mut resource_manager = ResourceManager::new();
+        resource_manager.load_from_str(r#"{"name":"wood","category":"raw","base_capacity":100,"modifiers":[]}"#).unwrap();
+        resource_manager.add_count("wood", to_number(10f64 - rate));
+
+        let mut production = ResourceStorage::new();
+        production.add("wood".to_string(), to_number(rate.max(0f64)));
+        let mut consumption = ResourceStorage::new();
+        consumption.add("wood".to_string(), to_number((-rate).max(0f64)));
+
+        resource_manager.set_production(&production);
+        resource_manager.set_consumption(&consumption);
+        resource_manager.produce(&ModifierStorage::new());
+        resource_manager.record_statistics();
+        resource_manager
+
+    }
+
+    fn report(price: f64, rate: f64) -> AffordabilityReport {
+
+        let mut prices = ResourceStorage::new();
+        prices.add("wood".to_string(), to_number(price));
+
+        AffordabilityReport::new(&prices, &resource_manager(rate))
+
+    }
+
+    #[test]
+    fn affordable_price_takes_no_ticks() {
+
+        let report = report(10f64, 0f64);
+
+        assert!(report.is_affordable);
+        assert_eq!(report.ticks, Some(0f64));
+
+    }
+
+    #[test]
+    fn missing_amount_is_divided_by_the_rate() {
+
+        let report = report(29f64, 2f64);
+
+        assert!(!report.is_affordable);
+        assert_eq!(report.ticks, Some(10f64));
+        assert_eq!(report.entries[0].ticks, Some(10f64));
+
+    }
+
+    #[test]
+    fn zero_or_negative_rate_never_affords() {
+
+        assert_eq!(report(20f64, 0f64).ticks, None);
+        assert_eq!(report(20f64, -1f64).ticks, None);
+
+    }
+
+    #[test]
+    fn price_above_capacity_never_affords() {
+
+        let report = report(150f64, 5f64);
+
+        assert!(report.exceeds_capacity);
+        assert_eq!(report.ticks, None);
+
+    }
+
+}
@@ -1,4 +1,5 @@
-use serde::Deserialize;
+use crate::core::thing::modifier::ModifierScaling;
+use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize)]
 pub struct BuildingAsset {
@@ -14,10 +15,101 @@ pub struct BuildingAsset {
     pub price_multiplier: f64,
     /// Size of the building.
     pub size: f64,
+    /// Maximum building count before modifiers.
+    #[serde(default)]
+    pub max_count: Option<i32>,
+    /// Is there only one of the building, such as a town hall?
+    #[serde(default)]
+    pub unique: bool,
+    /// Ticks the construction of a single building takes, built instantly if 0.
+    #[serde(default)]
+    pub build_ticks: u32,
 
     /// Productions of the buildings.
     pub productions: Vec<BuildingProduction>,
 
+    /// Building this building can be converted into.
+    #[serde(default)]
+    pub upgrades_into: Option<BuildingTier>,
+    /// Ratio of the price refunded when the building is demolished.
+    #[serde(default)]
+    pub refund_ratio: f64,
+
+    /// Requirements that unlock the building once all of them are met.
+    ///
+    /// A building without requirements is unlocked right away.
+    #[serde(default)]
+    pub requirements: Vec<BuildingRequirement>,
+    /// Requirements that show the locked building once all of them are met.
+    ///
+    /// A building without reveal requirements is always shown.
+    #[serde(default)]
+    pub reveal: Vec<BuildingRequirement>,
+
+}
+
+#[derive(Deserialize)]
+pub struct BuildingTier {
+
+    /// Name of the next tier building.
+    pub name: String,
+    /// Resource price of converting a single building, modified by the next tier's price modifiers.
+    ///
+    /// Without prices, the difference between the next tier's price and this building's price is paid.
+    #[serde(default)]
+    pub prices: Vec<BuildingPrice>,
+    /// Is the building hidden when it is obsolete and there is none left?
+    ///
+    /// The building is obsolete once the next tier is unlocked.
+    #[serde(default = "default_hide_obsolete")]
+    pub hide_obsolete: bool,
+
+}
+
+fn default_hide_obsolete() -> bool {
+
+    true
+
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BuildingRequirement {
+
+    /// The resource has been unlocked.
+    ResourceSeen {
+        /// Resource name.
+        name: String,
+    },
+    /// The resource count has reached the value.
+    ResourceCount {
+        /// Resource name.
+        name: String,
+        /// Required count.
+        count: f64,
+    },
+    /// The building count has reached the value.
+    BuildingCount {
+        /// Building name.
+        name: String,
+        /// Required count.
+        count: i32,
+    },
+    /// The upgrade count has reached the value.
+    Upgrade {
+        /// Upgrade name.
+        name: String,
+        /// Required count.
+        #[serde(default = "default_upgrade_count")]
+        count: i32,
+    },
+
+}
+
+fn default_upgrade_count() -> i32 {
+
+    1
+
 }
 
 #[derive(Deserialize)]
@@ -35,6 +127,22 @@ pub struct BuildingProduction {
 
     /// Production name.
     pub name: String,
+    /// Group of productions that cannot be active at the same time.
+    ///
+    /// Activating the production deactivates the other productions in the group.
+    #[serde(default)]
+    pub exclusive_group: Option<String>,
+    /// Resource price of switching into the production, per building.
+    #[serde(default)]
+    pub switch_prices: Vec<BuildingPrice>,
+    /// Ticks after switching into the production before it starts working.
+    #[serde(default)]
+    pub switch_ticks: u32,
+    /// Job slots of the production, per building.
+    ///
+    /// Outputs of a production with jobs depend on the workers assigned to the building.
+    #[serde(default)]
+    pub jobs: u32,
     /// Resource upkeep of the building production.
     pub upkeeps: Vec<BuildingUpkeep>,
     /// Resource output of the building production.
@@ -85,5 +193,8 @@ pub struct BuildingModifier {
     pub value: f64,
     /// Calculation method of the modifier.
     pub calculation: String,
+    /// Curve that scales the value by the active building count.
+    #[serde(default)]
+    pub scaling: ModifierScaling,
 
 }
@@ -0,0 +1,53 @@
+use crate::core::number::Number;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Building in the construction queue.
+///
+/// Saved as it is, so the queue survives reloading the game.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BuildingConstruction {
+
+    /// Identifier of the construction, unique within the queue.
+    pub id: u32,
+    /// Name of the building.
+    pub name: String,
+    /// Ticks of construction done.
+    #[serde(default)]
+    pub progress: u32,
+    /// Ticks the construction takes.
+    pub total_ticks: u32,
+    /// Resources paid for the building, refunded if the construction is cancelled.
+    #[serde(default)]
+    pub prices: BTreeMap<String, Number>,
+    /// Lower tier building converted into the building, given back if the construction is cancelled.
+    #[serde(default)]
+    pub converted_from: Option<String>,
+
+}
+
+impl BuildingConstruction {
+
+    /// Advances the construction by a tick, and returns true once it is finished.
+    pub fn advance(&mut self) -> bool {
+
+        self.progress = (self.progress + 1).min(self.total_ticks);
+        self.is_finished()
+
+    }
+
+    /// Is the construction finished?
+    pub fn is_finished(&self) -> bool {
+
+        self.progress >= self.total_ticks
+
+    }
+
+    /// Returns ratio of the construction done, between 0 and 1.
+    pub fn ratio(&self) -> f64 {
+
+        if self.total_ticks == 0 { 1f64 } else { self.progress as f64 / self.total_ticks as f64 }
+
+    }
+
+}
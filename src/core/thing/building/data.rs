@@ -1,7 +1,9 @@
 use super::BuildingAsset;
 use crate::core::thing::modifier::{ModifierCalculationMethod, ModifierEntry, ModifierStorage};
 use crate::core::thing::resource::ResourceStorage;
-use std::collections::HashSet;
+use crate::core::number::{to_f64, to_number, Number};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
 
 /// Building
 pub struct Building {
@@ -11,8 +13,16 @@ pub struct Building {
 
     /// Building count.
     count: i32,
-    /// Active building count.
+    /// Active building count, set by the player.
     active_count: i32,
+    /// Count of buildings in the construction queue.
+    queued_count: i32,
+    /// Population members assigned to the building's jobs.
+    workers: i32,
+    /// Reason why the active buildings are shut down automatically.
+    shutdown: Option<BuildingShutdown>,
+    /// Ratio of the output produced, limited by flow resources.
+    throttle: f64,
 
     /// Calculated building's upkeep.
     calculated_upkeeps: ResourceStorage,
@@ -24,11 +34,15 @@ pub struct Building {
     calculated_storages: ResourceStorage,
     /// Calculated building's price.
     calculated_prices: ResourceStorage,
+    /// Calculated maximum building count.
+    calculated_max_count: Option<i32>,
 
     /// Active building productions.
     active_productions: HashSet<String>,
     /// Unlocked productions or the building.
     unlocked_productions: HashSet<String>,
+    /// Ticks left until switched productions start working.
+    switching_productions: HashMap<String, u32>,
 
     /// Is the building unlocked?
     is_unlocked: bool,
@@ -46,17 +60,25 @@ impl From<BuildingAsset> for Building {
         let mut unlocked_productions = HashSet::new();
         unlocked_productions.insert("default".to_string());
 
+        let calculated_max_count = if asset.unique { Some(1) } else { asset.max_count.map(|v| v.max(0)) };
+
         Self {
             asset,
             count: 0,
             active_count: 0,
+            queued_count: 0,
+            workers: 0,
+            shutdown: None,
+            throttle: 1f64,
             calculated_upkeeps: ResourceStorage::new(),
             calculated_outputs: ResourceStorage::new(),
             calculated_modifiers: ModifierStorage::new(),
             calculated_storages: ResourceStorage::new(),
             calculated_prices: ResourceStorage::new(),
+            calculated_max_count,
             active_productions,
             unlocked_productions,
+            switching_productions: HashMap::new(),
             is_unlocked: false,
         }
 
@@ -87,6 +109,16 @@ impl Building {
 
     }
 
+    /// Returns true if any production of the building outputs or stores the resource.
+    pub fn supplies_resource(&self, name: &str) -> bool {
+
+        self.asset
+            .productions
+            .iter()
+            .any(|p| p.outputs.iter().any(|o| o.name == name) || p.storages.iter().any(|s| s.name == name))
+
+    }
+
 }
 
 /// Implementations related to building's count.
@@ -134,6 +166,94 @@ impl Building {
 
     }
 
+    /// Returns count of buildings in the construction queue.
+    pub fn queued_count(&self) -> i32 {
+
+        self.queued_count
+
+    }
+
+    /// Adds count of buildings in the construction queue.
+    pub fn add_queued_count(&mut self, count: i32) {
+
+        self.queued_count += count;
+
+    }
+
+    /// Sets count of buildings in the construction queue.
+    pub fn set_queued_count(&mut self, count: i32) {
+
+        self.queued_count = count;
+
+    }
+
+    /// Returns maximum building count, `None` if the count is not limited.
+    pub fn max_count(&self) -> Option<i32> {
+
+        self.calculated_max_count
+
+    }
+
+    /// Returns count of buildings that are actually working.
+    pub fn working_count(&self) -> i32 {
+
+        if self.shutdown.is_some() { 0 } else { self.active_count }
+
+    }
+
+    /// Returns the reason why the active buildings are shut down automatically.
+    pub fn shutdown(&self) -> Option<&BuildingShutdown> {
+
+        self.shutdown.as_ref()
+
+    }
+
+    /// Sets the reason why the active buildings are shut down automatically.
+    pub fn set_shutdown(&mut self, shutdown: Option<BuildingShutdown>) {
+
+        self.shutdown = shutdown;
+
+    }
+
+    /// Returns ratio of the output produced, limited by flow resources.
+    pub fn throttle(&self) -> f64 {
+
+        self.throttle
+
+    }
+
+    /// Sets ratio of the output produced.
+    pub fn set_throttle(&mut self, throttle: f64) {
+
+        self.throttle = throttle;
+
+    }
+
+}
+
+/// Reason why buildings are inactive.
+#[derive(Serialize, Copy, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum BuildingInactiveReason {
+    /// The player deactivated the buildings.
+    Player,
+    /// A resource the buildings upkeep is drained.
+    NoResource,
+    /// A flow resource the buildings upkeep is not supplied at all.
+    NoPower,
+}
+
+/// Automatic shutdown of active buildings.
+///
+/// Buildings are reactivated automatically once the resource is available again.
+#[derive(Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct BuildingShutdown {
+
+    /// Reason of the shutdown.
+    pub reason: BuildingInactiveReason,
+    /// Resource that caused the shutdown.
+    pub resource: String,
+
 }
 
 /// Implementations related to building's production.
@@ -181,18 +301,159 @@ impl Building {
     /// Sets active production.
     /// 
     /// The production must be unlocked to change its active state.
-    /// The 'default' production cannot be changed.
+    /// The 'default' production cannot be deactivated, but another production in its exclusive group can replace it.
+    /// The 'default' production is activated again once no production in its exclusive group is active.
+    /// Activating a production deactivates the other productions in its exclusive group and starts its switch delay.
     pub fn set_active_production(&mut self, key: &str, active: bool) {
         
-        if key == "default" { return; }
+        if !active && key == "default" { return; }
+        if !self.is_production_unlocked(key) { return; }
         
-        if self.is_production_unlocked(key) {
+        if !active {
             
-            if active { self.active_productions.insert(key.to_string()); }
-            else { self.active_productions.remove(key); }
+            self.active_productions.remove(key);
+            self.switching_productions.remove(key);
+            
+            if self.is_default_group_inactive() { self.set_active_production("default", true); }
+            return;
             
         }
         
+        if self.is_production_active(key) { return; }
+        
+        let Some(production) = self.asset.productions.iter().find(|p| p.name == key) else { return };
+        
+        if let Some(group) = &production.exclusive_group {
+            
+            for other in self.asset.productions.iter().filter(|p| p.name != key && p.exclusive_group.as_ref() == Some(group)) {
+                
+                self.active_productions.remove(&other.name);
+                self.switching_productions.remove(&other.name);
+                
+            }
+            
+        }
+        
+        if production.switch_ticks > 0 { self.switching_productions.insert(key.to_string(), production.switch_ticks); }
+        self.active_productions.insert(key.to_string());
+        
+    }
+    
+    /// Is the 'default' production in an exclusive group without any active production?
+    fn is_default_group_inactive(&self) -> bool {
+        
+        let Some(group) = self.asset.productions.iter().find(|p| p.name == "default").and_then(|p| p.exclusive_group.as_ref()) else { return false };
+        
+        !self.asset.productions
+            .iter()
+            .any(|p| p.exclusive_group.as_ref() == Some(group) && self.is_production_active(&p.name))
+        
+    }
+    
+    /// Takes over unlocked and active productions of another tier, matched by production name.
+    ///
+    /// Productions taken over are active right away, without their switch delay.
+    pub fn inherit_productions(&mut self, unlocked_productions: &[String], active_productions: &[String]) {
+        
+        for production in unlocked_productions.iter() {
+            
+            self.unlock_production(production);
+            
+        }
+        
+        for production in active_productions.iter() {
+            
+            self.set_active_production(production, true);
+            self.switching_productions.remove(production);
+            
+        }
+        
+    }
+    
+    /// Returns ticks left until the switched production starts working.
+    pub fn production_switch_ticks(&self, key: &str) -> u32 {
+        
+        self.switching_productions.get(key).copied().unwrap_or(0)
+        
+    }
+    
+    /// Returns ticks left until switched productions start working.
+    pub fn switching_productions(&self) -> &HashMap<String, u32> {
+        
+        &self.switching_productions
+        
+    }
+    
+    /// Restores active productions, such as from a save.
+    ///
+    /// Switch delays are restored as they are, without deactivating other productions or starting new delays.
+    pub fn restore_productions(&mut self, active_productions: &[String], switching_productions: impl IntoIterator<Item = (String, u32)>) {
+        
+        self.active_productions = active_productions
+            .iter()
+            .filter(|p| self.is_production_unlocked(p))
+            .cloned()
+            .collect();
+        
+        self.switching_productions = switching_productions
+            .into_iter()
+            .filter(|(p, ticks)| *ticks > 0 && self.active_productions.contains(p))
+            .collect();
+        
+    }
+    
+    /// Advances switch delays by a tick.
+    pub fn advance_production_switches(&mut self) {
+        
+        self.switching_productions.retain(|_, ticks| {
+            
+            *ticks -= 1;
+            *ticks > 0
+            
+        });
+        
+    }
+
+}
+
+/// Implementations related to building's jobs.
+impl Building {
+
+    /// Returns population members assigned to the building's jobs.
+    pub fn workers(&self) -> i32 {
+
+        self.workers
+
+    }
+
+    /// Sets population members assigned to the building's jobs.
+    pub fn set_workers(&mut self, workers: i32) {
+
+        self.workers = workers.max(0);
+
+    }
+
+    /// Returns job slots of the active buildings' working productions.
+    pub fn job_slots(&self) -> i32 {
+
+        let jobs = self.asset
+            .productions
+            .iter()
+            .filter(|p| self.active_productions.contains(&p.name) && !self.switching_productions.contains_key(&p.name))
+            .map(|p| p.jobs as i32)
+            .sum::<i32>();
+
+        jobs.saturating_mul(self.active_count.max(0))
+
+    }
+
+    /// Returns ratio of the job slots filled with workers, 1 if the building has no jobs.
+    pub fn staffing(&self) -> f64 {
+
+        let job_slots = self.job_slots();
+
+        if job_slots <= 0 { 1f64 } else { self.workers.clamp(0, job_slots) as f64 / job_slots as f64 }
+
     }
 
 }
@@ -207,10 +468,13 @@ impl Building {
         self.calculated_outputs.clear();
         self.calculated_modifiers.clear();
         self.calculated_storages.clear();
-        self.calculated_prices.clear();
+
+        let staffing = self.staffing();
 
         for production_name in self.active_productions.iter() {
 
+            if self.switching_productions.contains_key(production_name) { continue; }
+
             let production = self.asset
                 .productions
                 .iter()
@@ -224,15 +488,17 @@ impl Building {
 
                 }
 
+                let efficiency = if entry.jobs > 0 { staffing } else { 1f64 };
+
                 for output in entry.outputs.iter() {
 
-                    self.calculated_outputs.add(output.name.to_string(), self.create_modified_output_value(output.value, modifier_storage));
+                    self.calculated_outputs.add(output.name.to_string(), self.create_modified_output_value(output.value, efficiency, modifier_storage));
 
                 }
 
                 for modifier in entry.modifiers.iter() {
 
-                    self.calculated_modifiers.add(ModifierEntry::new(modifier.name.clone(), modifier.value * self.active_count as f64, ModifierCalculationMethod::from_str(modifier.calculation.as_str())));
+                    self.calculated_modifiers.add(ModifierEntry::new(modifier.name.clone(), to_number(modifier.value * modifier.scaling.evaluate(self.active_count as f64)), ModifierCalculationMethod::from_str(modifier.calculation.as_str())));
 
                 }
 
@@ -246,12 +512,61 @@ impl Building {
 
         }
 
+        self.calculate_prices(modifier_storage);
+        self.calculated_max_count = self.create_modified_max_count(modifier_storage);
+
+    }
+
+    /// Calculates building's price of the next purchase.
+    ///
+    /// Buildings in the construction queue are counted as built.
+    pub fn calculate_prices(&mut self, modifier_storage: &ModifierStorage) {
+
+        self.calculated_prices = self.prices_at(self.count + self.queued_count, modifier_storage);
+
+    }
+
+    /// Creates price of the building when there are already `count` buildings.
+    pub fn prices_at(&self, count: i32, modifier_storage: &ModifierStorage) -> ResourceStorage {
+
+        let mut prices = ResourceStorage::new();
+
         for price in self.asset.prices.iter() {
 
-            self.calculated_prices.add(price.name.to_string(), self.create_modified_price(price.value, modifier_storage));
+            prices.add(price.name.to_string(), self.create_modified_price(price.value, count.max(0), modifier_storage));
 
         }
 
+        prices
+
+    }
+
+    /// Creates a fixed price of the building, such as a conversion price, modified by price modifiers but not by the price multiplier.
+    pub fn modified_price(&self, value: f64, modifier_storage: &ModifierStorage) -> Number {
+
+        self.create_modified_price(value, 0, modifier_storage)
+
+    }
+
+    /// Returns true if the modifier is used when calculating the building.
+    pub fn is_affected_by_modifier(&self, name: &str) -> bool {
+
+        name == "global.speed" ||
+            name.starts_with("building.global.") ||
+            name.starts_with(&format!("building.name.{}.", self.asset.name)) ||
+            name.starts_with(&format!("building.category.{}.", self.asset.category))
+
+    }
+
+    /// Returns true if any production of the building can generate a modifier that affects the other building.
+    pub fn can_affect(&self, other: &Building) -> bool {
+
+        self.asset
+            .productions
+            .iter()
+            .flat_map(|production| production.modifiers.iter())
+            .any(|modifier| other.is_affected_by_modifier(&modifier.name))
+
     }
 
     /// Calculated upkeep of the building.
@@ -290,9 +605,9 @@ impl Building {
     }
 
     /// Creates upkeep value using modifiers.
-    fn create_modified_upkeep_value(&self, original_value: f64, modifier_storage: &ModifierStorage) -> f64 {
+    fn create_modified_upkeep_value(&self, original_value: f64, modifier_storage: &ModifierStorage) -> Number {
 
-        let mut value = original_value;
+        let mut value = to_number(original_value);
         value += 0f64 +
             modifier_storage.value(&format!("building.name.{}.upkeep", self.asset.name), ModifierCalculationMethod::Base) +
             modifier_storage.value(&format!("building.category.{}.upkeep", self.asset.category), ModifierCalculationMethod::Base) +
@@ -313,9 +628,11 @@ impl Building {
     }
 
     /// Creates output value using modifiers.
-    fn create_modified_output_value(&self, original_value: f64, modifier_storage: &ModifierStorage) -> f64 {
+    ///
+    /// The `efficiency` scales the output of the active buildings, such as by the staffing of their jobs.
+    fn create_modified_output_value(&self, original_value: f64, efficiency: f64, modifier_storage: &ModifierStorage) -> Number {
 
-        let mut value = original_value;
+        let mut value = to_number(original_value);
         value += 0f64 +
             modifier_storage.value(&format!("building.name.{}.output", self.asset.name), ModifierCalculationMethod::Base) +
             modifier_storage.value(&format!("building.category.{}.output", self.asset.category), ModifierCalculationMethod::Base) +
@@ -328,17 +645,17 @@ impl Building {
             modifier_storage.value(&format!("building.name.{}.output", self.asset.name), ModifierCalculationMethod::Flat) +
             modifier_storage.value(&format!("building.category.{}.output", self.asset.category), ModifierCalculationMethod::Flat) +
             modifier_storage.value(&format!("building.global.output"), ModifierCalculationMethod::Flat);
-        value *= (1f64 + modifier_storage.value("global.speed", ModifierCalculationMethod::Additive)).min(0f64);
-        value *= self.active_count as f64;
+        value *= (1f64 + modifier_storage.value("global.speed", ModifierCalculationMethod::Additive)).max(0f64);
+        value *= self.active_count as f64 * efficiency;
 
         value.max(0f64).floor()
 
     }
 
     /// Creates storage value using modifiers.
-    fn create_modified_storage_value(&self, original_value: f64, modifier_storage: &ModifierStorage) -> f64 {
+    fn create_modified_storage_value(&self, original_value: f64, modifier_storage: &ModifierStorage) -> Number {
 
-        let mut value = original_value;
+        let mut value = to_number(original_value);
         value += 0f64 +
             modifier_storage.value(&format!("building.name.{}.storage", self.asset.name), ModifierCalculationMethod::Base) +
             modifier_storage.value(&format!("building.category.{}.storage", self.asset.category), ModifierCalculationMethod::Base) +
@@ -357,13 +674,38 @@ impl Building {
 
     }
 
+    /// Creates maximum building count using modifiers.
+    ///
+    /// Unique buildings are limited to one, and buildings without a maximum count are not limited.
+    fn create_modified_max_count(&self, modifier_storage: &ModifierStorage) -> Option<i32> {
+
+        if self.asset.unique { return Some(1); }
+
+        let mut value = to_number(self.asset.max_count? as f64);
+        value += 0f64 +
+            modifier_storage.value(&format!("building.name.{}.max_count", self.asset.name), ModifierCalculationMethod::Base) +
+            modifier_storage.value(&format!("building.category.{}.max_count", self.asset.category), ModifierCalculationMethod::Base) +
+            modifier_storage.value("building.global.max_count", ModifierCalculationMethod::Base);
+        value *= 1f64 +
+            modifier_storage.value(&format!("building.name.{}.max_count", self.asset.name), ModifierCalculationMethod::Additive) +
+            modifier_storage.value(&format!("building.category.{}.max_count", self.asset.category), ModifierCalculationMethod::Additive) +
+            modifier_storage.value("building.global.max_count", ModifierCalculationMethod::Additive);
+        value += 0f64 +
+            modifier_storage.value(&format!("building.name.{}.max_count", self.asset.name), ModifierCalculationMethod::Flat) +
+            modifier_storage.value(&format!("building.category.{}.max_count", self.asset.category), ModifierCalculationMethod::Flat) +
+            modifier_storage.value("building.global.max_count", ModifierCalculationMethod::Flat);
+
+        Some(to_f64(value.max(0f64).floor()).min(i32::MAX as f64) as i32)
+
+    }
+
     /// Creates price value using modifiers.
-    fn create_modified_price(&self, original_value: f64, modifier_storage: &ModifierStorage) -> f64 {
+    fn create_modified_price(&self, original_value: f64, count: i32, modifier_storage: &ModifierStorage) -> Number {
 
-        let mut value = original_value;
-        let mut value_division = 1f64;
-        let mut price_multiplier = self.asset.price_multiplier.max(1f64);
-        let mut price_multiplier_division = 1f64;
+        let mut value = to_number(original_value);
+        let mut value_division = to_number(1f64);
+        let mut price_multiplier = to_number(self.asset.price_multiplier.max(1f64));
+        let mut price_multiplier_division = to_number(1f64);
         
         value += 0f64 +
             modifier_storage.value(&format!("building.name.{}.price", self.asset.name), ModifierCalculationMethod::Base) +
@@ -406,7 +748,7 @@ impl Building {
 
         price_multiplier = ((price_multiplier - 1f64) / price_multiplier_division).max(0f64) + 1f64;
         
-        value = value / value_division.min(1f64) * price_multiplier.powi(self.count);
+        value = value / value_division.min(1f64) * price_multiplier.powi(count);
         
         value.max(1f64).floor()
         
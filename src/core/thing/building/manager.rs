@@ -1,8 +1,16 @@
-use crate::core::thing::building::{Building, BuildingAsset};
-use crate::core::thing::modifier::ModifierStorage;
-use crate::core::thing::resource::{ResourceManager, ResourceStorage};
+use crate::core::thing::building::{Building, BuildingAsset, BuildingConstruction, BuildingConstructionQueueSnapshot, BuildingConversionSnapshot, BuildingInactiveReason, BuildingRequirement, BuildingRequirementSnapshot, BuildingSave, BuildingShutdown, BuildingSnapshot};
+use crate::core::thing::snapshot::ResourceValueSnapshot;
+use crate::core::thing::graph::strongly_connected_components;
+use crate::core::thing::modifier::{ModifierCalculationMethod, ModifierStorage};
+use crate::core::thing::resource::{flow_utilization, ResourceManager, ResourceStorage};
+use crate::core::thing::upgrade::UpgradeManager;
 use std::collections::hash_map::Iter;
 use std::collections::HashMap;
+use thiserror::Error;
+use crate::core::number::{to_f64, to_number, Number};
+
+/// Maximum number of passes used for resolving buildings that affect each other.
+const MAX_MODIFIER_ITERATIONS: usize = 16;
 
 /// Building manager.
 pub struct BuildingManager {
@@ -19,6 +27,20 @@ pub struct BuildingManager {
     /// Calculated building storages.
     calculated_storages: ResourceStorage,
 
+    /// Groups of building names in the order they must be calculated.
+    calculation_order: Vec<Vec<String>>,
+    /// Groups of building names that generate modifiers for each other or for themselves.
+    calculation_cycles: Vec<Vec<String>>,
+    /// Groups of building names whose modifiers did not settle in the last calculation.
+    unresolved_cycles: Vec<Vec<String>>,
+
+    /// Buildings waiting to be constructed, in the order they were purchased.
+    construction_queue: Vec<BuildingConstruction>,
+    /// Identifier of the next construction.
+    next_construction_id: u32,
+    /// Calculated count of buildings constructed at the same time.
+    calculated_build_slots: u32,
+
 }
 
 impl BuildingManager {
@@ -32,6 +54,12 @@ impl BuildingManager {
             calculated_outputs: ResourceStorage::new(),
             calculated_modifiers: ModifierStorage::new(),
             calculated_storages: ResourceStorage::new(),
+            calculation_order: Vec::new(),
+            calculation_cycles: Vec::new(),
+            unresolved_cycles: Vec::new(),
+            construction_queue: Vec::new(),
+            next_construction_id: 0,
+            calculated_build_slots: 1,
         }
         
     }
@@ -122,6 +150,40 @@ impl BuildingManager {
     pub fn add(&mut self, building: Building) {
 
         self.buildings.insert(building.asset().name.clone(), building);
+        self.update_calculation_order();
+
+    }
+
+    /// Sorts buildings so that buildings generating modifiers are calculated before the buildings they affect.
+    ///
+    /// A building that generates modifiers for itself depends on itself, so it is resolved like a cycle.
+    fn update_calculation_order(&mut self) {
+
+        let mut names = self.buildings.keys().cloned().collect::<Vec<_>>();
+        names.sort();
+
+        let dependencies = names
+            .iter()
+            .map(|name| {
+
+                let building = &self.buildings[name];
+                let sources = names
+                    .iter()
+                    .filter(|source| self.buildings[*source].can_affect(building))
+                    .cloned()
+                    .collect::<Vec<_>>();
+
+                (name.clone(), sources)
+
+            })
+            .collect::<HashMap<_, _>>();
+
+        self.calculation_order = strongly_connected_components(&names, &dependencies);
+        self.calculation_cycles = self.calculation_order
+            .iter()
+            .filter(|group| group.len() > 1 || dependencies[&group[0]].contains(&group[0]))
+            .cloned()
+            .collect();
 
     }
     
@@ -132,6 +194,12 @@ impl BuildingManager {
 
     /// Calculates all buildings.
     ///
+    /// Buildings are calculated in dependency order, so modifiers generated by a building apply within the same tick.
+    /// Buildings that affect each other or themselves are calculated repeatedly until their modifiers stop changing.
+    /// Groups that do not settle within the iteration limit are reported by `unresolved_cycles`.
+    /// Outputs of buildings that upkeep flow resources are throttled by the utilization of those resources.
+    /// Buildings are shut down while a resource they upkeep is drained or not supplied, and work again once it is available.
+    ///
     /// # Params
     ///
     /// - `modifier_storage`: Modifiers that can be used for calculating buildings.
@@ -143,23 +211,61 @@ impl BuildingManager {
         self.calculated_modifiers.clear();
         self.calculated_storages.clear();
 
-        for (_, building) in self.buildings.iter_mut() {
+        self.unresolved_cycles.clear();
 
-            building.calculate(modifier_storage);
-            let is_drained = building.calculated_upkeeps()
-                .iter()
-                .any(|(name, _)| {
-                    resource_manager.is_drained(name)
-                });
+        let mut resolved_modifiers = modifier_storage.clone();
 
-            if is_drained {
+        for group in self.calculation_order.iter() {
 
-                self.calculated_upkeeps.combine(building.calculated_upkeeps());
+            let is_cycle = self.calculation_cycles.contains(group);
+            let mut is_resolved = false;
+            let mut group_modifiers = ModifierStorage::new();
+
+            for _ in 0..MAX_MODIFIER_ITERATIONS {
+
+                let mut current_modifiers = resolved_modifiers.clone();
+                current_modifiers.combine(&group_modifiers);
+
+                let mut next_group_modifiers = ModifierStorage::new();
+
+                for name in group.iter() {
+
+                    if let Some(building) = self.buildings.get_mut(name) {
+
+                        building.calculate(&current_modifiers);
+                        building.set_shutdown(Self::find_shutdown(building, resource_manager));
+
+                        if building.shutdown().is_none() {
+
+                            next_group_modifiers.combine(building.calculated_modifiers());
+
+                        }
+
+                    }
+
+                }
+
+                is_resolved = !is_cycle || next_group_modifiers == group_modifiers;
+                group_modifiers = next_group_modifiers;
+
+                if is_resolved { break; }
+
+            }
+
+            if !is_resolved { self.unresolved_cycles.push(group.clone()); }
+
+            resolved_modifiers.combine(&group_modifiers);
+
+        }
+
+        let mut unthrottled_outputs = ResourceStorage::new();
 
-            } else {
+        for (_, building) in self.buildings.iter() {
+
+            if building.shutdown().is_none() {
 
                 self.calculated_upkeeps.combine(building.calculated_upkeeps());
-                self.calculated_outputs.combine(building.calculated_outputs());
+                unthrottled_outputs.combine(building.calculated_outputs());
                 self.calculated_modifiers.combine(building.calculated_modifiers());
                 self.calculated_storages.combine(building.calculated_storages());
 
@@ -167,8 +273,79 @@ impl BuildingManager {
 
         }
 
+        for (_, building) in self.buildings.iter_mut() {
+
+            if building.shutdown().is_some() {
+
+                building.set_throttle(0f64);
+                continue;
+
+            }
+
+            let throttle = building.calculated_upkeeps()
+                .iter()
+                .filter(|(name, _)| resource_manager.is_flow(name))
+                .map(|(name, _)| flow_utilization(unthrottled_outputs.value(name), self.calculated_upkeeps.value(name)))
+                .fold(1f64, f64::min);
+
+            building.set_throttle(throttle);
+            self.calculated_outputs.combine_scaled(building.calculated_outputs(), throttle);
+
+        }
+
+        for (_, building) in self.buildings.iter_mut() {
+
+            building.advance_production_switches();
+
+        }
+
     }
-    
+
+    /// Finds why the building has to be shut down, if a resource it upkeeps is drained or not supplied.
+    ///
+    /// Upkeeps of shut down buildings are not consumed, so a shut down building restarts only if resources can also cover its own upkeeps.
+    /// Resources are checked in name order, so the reported resource is stable.
+    fn find_shutdown(building: &Building, resource_manager: &ResourceManager) -> Option<BuildingShutdown> {
+
+        let is_shut_down = building.shutdown().is_some();
+
+        let mut upkeeps = building.calculated_upkeeps()
+            .iter()
+            .collect::<Vec<_>>();
+
+        upkeeps.sort_by_key(|(name, _)| *name);
+        upkeeps
+            .into_iter()
+            .find_map(|(name, value)| {
+
+                let demand = if is_shut_down { *value } else { to_number(0f64) };
+
+                let reason = if resource_manager.is_flow(name) {
+                    if resource_manager.is_unsupplied_with(name, demand) { Some(BuildingInactiveReason::NoPower) } else { None }
+                } else if resource_manager.is_drained_with(name, demand) { Some(BuildingInactiveReason::NoResource) } else { None };
+
+                reason.map(|reason| BuildingShutdown { reason, resource: name.clone() })
+
+            })
+
+    }
+
+    /// Returns groups of buildings that generate modifiers for each other or for themselves.
+    pub fn calculation_cycles(&self) -> &[Vec<String>] {
+
+        &self.calculation_cycles
+
+    }
+
+    /// Returns groups of buildings whose modifiers did not settle within the iteration limit in the last calculation.
+    ///
+    /// The modifiers of the last iteration are used for these groups.
+    pub fn unresolved_cycles(&self) -> &[Vec<String>] {
+
+        &self.unresolved_cycles
+
+    }
+
     /// Returns calculated upkeeps.
     pub fn calculated_upkeeps(&self) -> &ResourceStorage {
 
@@ -198,3 +375,1644 @@ impl BuildingManager {
     }
     
 }
+
+#[derive(Error, Debug)]
+pub enum BuildingPurchaseError {
+    #[error("building '{0}' does not exist")]
+    NotFound(String),
+    #[error("building '{0}' is locked")]
+    Locked(String),
+    #[error("building '{0}' is replaced by '{1}'")]
+    Obsolete(String, String),
+    #[error("building '{0}' is limited to {1}")]
+    LimitReached(String, i32),
+    #[error("not enough resource '{0}'")]
+    NotEnoughResource(String),
+    #[error("not enough space, {needed} needed but {free} free")]
+    NotEnoughSpace { needed: Number, free: Number },
+}
+
+#[derive(Error, Debug)]
+pub enum BuildingConversionError {
+    #[error("building '{0}' does not exist")]
+    NotFound(String),
+    #[error("building '{0}' does not upgrade into another building")]
+    NoNextTier(String),
+    #[error("building '{0}' is locked")]
+    Locked(String),
+    #[error("not enough building '{0}', {1} needed")]
+    NotEnoughBuilding(String, i32),
+    #[error("building '{0}' is limited to {1}")]
+    LimitReached(String, i32),
+    #[error("not enough resource '{0}'")]
+    NotEnoughResource(String),
+    #[error("not enough space, {needed} needed but {free} free")]
+    NotEnoughSpace { needed: Number, free: Number },
+}
+
+#[derive(Error, Debug)]
+pub enum BuildingActivationError {
+    #[error("building '{0}' does not exist")]
+    NotFound(String),
+}
+
+#[derive(Error, Debug)]
+pub enum BuildingWorkerError {
+    #[error("building '{0}' does not exist")]
+    NotFound(String),
+    #[error("building '{0}' has no jobs")]
+    NoJobs(String),
+}
+
+#[derive(Error, Debug)]
+pub enum BuildingProductionError {
+    #[error("building '{0}' does not exist")]
+    NotFound(String),
+    #[error("production '{1}' of building '{0}' does not exist")]
+    ProductionNotFound(String, String),
+    #[error("production '{1}' of building '{0}' is locked")]
+    ProductionLocked(String, String),
+    #[error("production 'default' of building '{0}' cannot be deactivated")]
+    DefaultProduction(String),
+    #[error("not enough resource '{0}'")]
+    NotEnoughResource(String),
+}
+
+#[derive(Error, Debug)]
+pub enum BuildingConstructionError {
+    #[error("construction '{0}' does not exist")]
+    NotFound(u32),
+}
+
+#[derive(Error, Debug)]
+pub enum BuildingDemolishError {
+    #[error("building '{0}' does not exist")]
+    NotFound(String),
+    #[error("building '{0}' has not been built")]
+    NotBuilt(String),
+}
+
+/// Implementations related to purchasing, activating and demolishing buildings.
+impl BuildingManager {
+
+    /// Purchases an unlocked building.
+    ///
+    /// The price is taken from the resources, and the new building is active.
+    /// Buildings with a build time are added to the construction queue instead, and are active once they are built.
+    ///
+    /// # Params
+    ///
+    /// - `free_space`: Space left for buildings, `None` if the land is not limited.
+    /// - `modifier_storage`: Modifiers used for calculating the price of the next purchase.
+    pub fn purchase(&mut self, name: &str, free_space: Option<Number>, resource_manager: &mut ResourceManager, modifier_storage: &ModifierStorage) -> Result<(), BuildingPurchaseError> {
+
+        if let Some(next_tier) = self.next_tier(name).filter(|next_tier| next_tier.is_unlocked()) {
+
+            return Err(BuildingPurchaseError::Obsolete(name.to_string(), next_tier.asset().name.clone()));
+
+        }
+
+        let building = self.buildings
+            .get_mut(name)
+            .ok_or_else(|| BuildingPurchaseError::NotFound(name.to_string()))?;
+
+        if !building.is_unlocked() {
+
+            return Err(BuildingPurchaseError::Locked(name.to_string()));
+
+        }
+
+        if let Some(max_count) = building.max_count().filter(|max_count| building.count() + building.queued_count() >= *max_count) {
+
+            return Err(BuildingPurchaseError::LimitReached(name.to_string(), max_count));
+
+        }
+
+        let size = to_number(building.asset().size.max(0f64));
+        if let Some(free) = free_space.filter(|free| size > *free) {
+
+            return Err(BuildingPurchaseError::NotEnoughSpace { needed: size, free });
+
+        }
+
+        if let Some((resource, _)) = building.calculated_prices()
+            .iter()
+            .find(|(resource, value)| resource_manager.count(resource) < **value) {
+
+            return Err(BuildingPurchaseError::NotEnoughResource(resource.clone()));
+
+        }
+
+        for (resource, value) in building.calculated_prices().iter() {
+
+            resource_manager.add_count(resource, -*value);
+
+        }
+
+        if building.asset().build_ticks > 0 {
+
+            self.construction_queue.push(BuildingConstruction {
+                id: self.next_construction_id,
+                name: name.to_string(),
+                progress: 0,
+                total_ticks: building.asset().build_ticks,
+                prices: building.calculated_prices().iter().map(|(resource, value)| (resource.clone(), *value)).collect(),
+                converted_from: None,
+            });
+
+            self.next_construction_id += 1;
+            building.add_queued_count(1);
+
+        } else {
+
+            building.add_count(1);
+            building.add_active_count(1);
+
+        }
+
+        building.calculate_prices(modifier_storage);
+
+        Ok(())
+
+    }
+
+    /// Sets how many of the buildings are active, and returns the count after clamping to the building count.
+    ///
+    /// Buildings that are shut down automatically stay shut down until the resource is available again.
+    pub fn activate(&mut self, name: &str, active_count: i32) -> Result<i32, BuildingActivationError> {
+
+        let building = self.buildings
+            .get_mut(name)
+            .ok_or_else(|| BuildingActivationError::NotFound(name.to_string()))?;
+
+        building.set_active_count(active_count.clamp(0, building.count().max(0)));
+
+        Ok(building.active_count())
+
+    }
+
+    /// Activates or deactivates a production of the building.
+    ///
+    /// Activating a production deactivates the other productions in its exclusive group.
+    /// Deactivating the last active production in the exclusive group of 'default' activates 'default' again.
+    /// The switch price of the production is paid for every building, and the production works after its switch delay.
+    pub fn set_active_production(&mut self, name: &str, production: &str, active: bool, resource_manager: &mut ResourceManager) -> Result<(), BuildingProductionError> {
+
+        let building = self.buildings
+            .get_mut(name)
+            .ok_or_else(|| BuildingProductionError::NotFound(name.to_string()))?;
+
+        let asset = building.asset()
+            .productions
+            .iter()
+            .find(|p| p.name == production)
+            .ok_or_else(|| BuildingProductionError::ProductionNotFound(name.to_string(), production.to_string()))?;
+
+        if !building.is_production_unlocked(production) {
+
+            return Err(BuildingProductionError::ProductionLocked(name.to_string(), production.to_string()));
+
+        }
+
+        if !active && production == "default" {
+
+            return Err(BuildingProductionError::DefaultProduction(name.to_string()));
+
+        }
+
+        if active && !building.is_production_active(production) {
+
+            let count = building.count().max(0) as f64;
+
+            if let Some(price) = asset.switch_prices
+                .iter()
+                .find(|price| resource_manager.count(&price.name) < price.value * count) {
+
+                return Err(BuildingProductionError::NotEnoughResource(price.name.clone()));
+
+            }
+
+            for price in asset.switch_prices.iter() {
+
+                resource_manager.add_count(&price.name, to_number(-price.value * count));
+
+            }
+
+        }
+
+        building.set_active_production(production, active);
+
+        Ok(())
+
+    }
+
+    /// Demolishes a building, which frees its space.
+    ///
+    /// The refund ratio of the price the building cost is returned to the resources.
+    /// Buildings converted from a lower tier are refunded by their own price like purchased ones, and are not turned back into the lower tier.
+    pub fn demolish(&mut self, name: &str, resource_manager: &mut ResourceManager, modifier_storage: &ModifierStorage) -> Result<(), BuildingDemolishError> {
+
+        let building = self.buildings
+            .get_mut(name)
+            .ok_or_else(|| BuildingDemolishError::NotFound(name.to_string()))?;
+
+        if building.count() <= 0 {
+
+            return Err(BuildingDemolishError::NotBuilt(name.to_string()));
+
+        }
+
+        let refund_ratio = building.asset().refund_ratio.clamp(0f64, 1f64);
+        if refund_ratio > 0f64 {
+
+            for (resource, value) in building.prices_at(building.count() - 1, modifier_storage).iter() {
+
+                resource_manager.add_count(resource, *value * refund_ratio);
+
+            }
+
+        }
+
+        building.add_count(-1);
+        building.set_active_count(building.active_count().min(building.count()));
+        building.calculate_prices(modifier_storage);
+
+        Ok(())
+
+    }
+
+}
+
+/// Implementations related to building jobs.
+impl BuildingManager {
+
+    /// Returns population members assigned to jobs of all buildings.
+    pub fn total_workers(&self) -> i32 {
+
+        self.buildings.values().map(|b| b.workers()).sum()
+
+    }
+
+    /// Returns job slots of all buildings.
+    pub fn total_job_slots(&self) -> i32 {
+
+        self.buildings.values().map(|b| b.job_slots()).sum()
+
+    }
+
+    /// Assigns workers to the building's jobs, and returns the workers after clamping.
+    ///
+    /// Workers are limited by the building's job slots and by the population members that are not assigned elsewhere.
+    ///
+    /// # Params
+    ///
+    /// - `population`: Count of population members that can work.
+    pub fn assign_workers(&mut self, name: &str, workers: i32, population: i32) -> Result<i32, BuildingWorkerError> {
+
+        let assigned_elsewhere = self.total_workers() - self.buildings.get(name).map(|b| b.workers()).unwrap_or(0);
+
+        let building = self.buildings
+            .get_mut(name)
+            .ok_or_else(|| BuildingWorkerError::NotFound(name.to_string()))?;
+
+        let job_slots = building.job_slots();
+        if job_slots <= 0 && workers > 0 {
+
+            return Err(BuildingWorkerError::NoJobs(name.to_string()));
+
+        }
+
+        building.set_workers(workers.clamp(0, job_slots.min((population - assigned_elsewhere).max(0))));
+
+        Ok(building.workers())
+
+    }
+
+    /// Releases workers that no longer have a job or a population member to fill it.
+    ///
+    /// Workers above the job slots are released first. If the population is still too small,
+    /// workers are released from buildings in name order.
+    pub fn limit_workers(&mut self, population: i32) {
+
+        for building in self.buildings.values_mut() {
+
+            building.set_workers(building.workers().min(building.job_slots()));
+
+        }
+
+        let mut excess = self.total_workers() - population.max(0);
+        if excess <= 0 { return; }
+
+        let mut names = self.buildings
+            .iter()
+            .filter(|(_, b)| b.workers() > 0)
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<_>>();
+
+        names.sort();
+
+        for name in names.iter() {
+
+            let Some(building) = self.buildings.get_mut(name) else { continue };
+            let released = building.workers().min(excess);
+
+            building.set_workers(building.workers() - released);
+            excess -= released;
+
+            if excess <= 0 { break; }
+
+        }
+
+    }
+
+}
+
+/// Implementations related to constructing buildings.
+impl BuildingManager {
+
+    /// Returns buildings waiting to be constructed, in the order they were purchased.
+    pub fn construction_queue(&self) -> &[BuildingConstruction] {
+
+        &self.construction_queue
+
+    }
+
+    /// Returns count of buildings constructed at the same time.
+    pub fn build_slots(&self) -> u32 {
+
+        self.calculated_build_slots
+
+    }
+
+    /// Advances constructions in the build slots by a tick, and returns names of the finished buildings.
+    ///
+    /// Finished buildings are added to the building count and are active.
+    ///
+    /// # Params
+    ///
+    /// - `modifier_storage`: Modifiers that can change the build slots, `building.global.build_slots`.
+    pub fn advance_construction(&mut self, modifier_storage: &ModifierStorage) -> Vec<String> {
+
+        self.calculated_build_slots = Self::create_modified_build_slots(modifier_storage);
+
+        let slots = self.calculated_build_slots as usize;
+        self.construction_queue
+            .iter_mut()
+            .take(slots)
+            .for_each(|construction| { construction.advance(); });
+
+        let (finished, queue) = std::mem::take(&mut self.construction_queue)
+            .into_iter()
+            .partition::<Vec<_>, _>(|construction| construction.is_finished());
+
+        self.construction_queue = queue;
+
+        finished
+            .into_iter()
+            .filter_map(|construction| {
+
+                let building = self.buildings.get_mut(&construction.name)?;
+                building.add_count(1);
+                building.add_active_count(1);
+                building.add_queued_count(-1);
+
+                Some(construction.name)
+
+            })
+            .collect()
+
+    }
+
+    /// Cancels a construction, refunding the price paid for the building.
+    ///
+    /// A cancelled conversion also gives back the converted lower tier building, which is active.
+    ///
+    /// # Params
+    ///
+    /// - `modifier_storage`: Modifiers used for calculating the price of the next purchase.
+    pub fn cancel_construction(&mut self, id: u32, resource_manager: &mut ResourceManager, modifier_storage: &ModifierStorage) -> Result<(), BuildingConstructionError> {
+
+        let index = self.construction_queue
+            .iter()
+            .position(|construction| construction.id == id)
+            .ok_or(BuildingConstructionError::NotFound(id))?;
+
+        let construction = self.construction_queue.remove(index);
+
+        for (resource, value) in construction.prices.iter() {
+
+            resource_manager.add_count(resource, *value);
+
+        }
+
+        if let Some(building) = self.buildings.get_mut(&construction.name) {
+
+            building.add_queued_count(-1);
+            building.calculate_prices(modifier_storage);
+
+        }
+
+        if let Some(building) = construction.converted_from.and_then(|name| self.buildings.get_mut(&name)) {
+
+            building.add_count(1);
+            building.add_active_count(1);
+            building.calculate_prices(modifier_storage);
+
+        }
+
+        Ok(())
+
+    }
+
+    /// Creates count of build slots using modifiers, at least 1.
+    fn create_modified_build_slots(modifier_storage: &ModifierStorage) -> u32 {
+
+        let mut value = to_number(1f64);
+        value += modifier_storage.value("building.global.build_slots", ModifierCalculationMethod::Base);
+        value *= 1f64 + modifier_storage.value("building.global.build_slots", ModifierCalculationMethod::Additive);
+        value += modifier_storage.value("building.global.build_slots", ModifierCalculationMethod::Flat);
+
+        to_f64(value.floor()).clamp(1f64, u32::MAX as f64) as u32
+
+    }
+
+}
+
+/// Implementations related to building tiers.
+impl BuildingManager {
+
+    /// Returns the building the building upgrades into.
+    pub fn next_tier(&self, name: &str) -> Option<&Building> {
+
+        self.buildings
+            .get(name)
+            .and_then(|b| b.asset().upgrades_into.as_ref())
+            .and_then(|tier| self.buildings.get(&tier.name))
+
+    }
+
+    /// Is the building replaced by its unlocked next tier?
+    pub fn is_obsolete(&self, name: &str) -> bool {
+
+        self.next_tier(name).is_some_and(|b| b.is_unlocked())
+
+    }
+
+    /// Creates price of converting buildings into the next tier.
+    ///
+    /// Without conversion prices, the next tier's price minus the price of the converted buildings is paid for every resource.
+    pub fn conversion_prices(&self, name: &str, count: i32, modifier_storage: &ModifierStorage) -> Result<ResourceStorage, BuildingConversionError> {
+
+        let mut prices = ResourceStorage::new();
+
+        for building_prices in self.conversion_prices_per_building(name, count, modifier_storage)?.iter() {
+
+            prices.combine(building_prices);
+
+        }
+
+        Ok(prices)
+
+    }
+
+    /// Creates price of converting each building into the next tier, in the order they are converted.
+    ///
+    /// Prices continue from the buildings already built or queued, like purchases, and conversion prices are modified by the next tier's price modifiers.
+    fn conversion_prices_per_building(&self, name: &str, count: i32, modifier_storage: &ModifierStorage) -> Result<Vec<ResourceStorage>, BuildingConversionError> {
+
+        let building = self.buildings
+            .get(name)
+            .ok_or_else(|| BuildingConversionError::NotFound(name.to_string()))?;
+
+        let tier = building.asset()
+            .upgrades_into
+            .as_ref()
+            .ok_or_else(|| BuildingConversionError::NoNextTier(name.to_string()))?;
+
+        let next_tier = self.buildings
+            .get(&tier.name)
+            .ok_or_else(|| BuildingConversionError::NotFound(tier.name.clone()))?;
+
+        let prices = (0..count.max(0))
+            .map(|i| {
+
+                let mut prices = ResourceStorage::new();
+
+                if !tier.prices.is_empty() {
+
+                    for price in tier.prices.iter() {
+
+                        prices.add(price.name.clone(), next_tier.modified_price(price.value, modifier_storage));
+
+                    }
+
+                    return prices;
+
+                }
+
+                let refunds = building.prices_at(building.count() + building.queued_count() - 1 - i, modifier_storage);
+
+                for (resource, value) in next_tier.prices_at(next_tier.count() + next_tier.queued_count() + i, modifier_storage).iter() {
+
+                    prices.add(resource.clone(), (*value - refunds.value(resource)).max(0f64));
+
+                }
+
+                prices
+
+            })
+            .collect();
+
+        Ok(prices)
+
+    }
+
+    /// Converts buildings into the next tier.
+    ///
+    /// Inactive buildings are converted first, and the next tier takes over productions with the same names.
+    /// If the next tier takes time to build, converted buildings are removed right away and the next tier is queued for construction,
+    /// where it is active once finished like purchased buildings.
+    ///
+    /// # Params
+    ///
+    /// - `free_space`: Space left for buildings, `None` if the land is not limited.
+    /// - `modifier_storage`: Modifiers used for calculating prices.
+    pub fn convert(&mut self, name: &str, count: i32, free_space: Option<Number>, resource_manager: &mut ResourceManager, modifier_storage: &ModifierStorage) -> Result<(), BuildingConversionError> {
+
+        let prices = self.conversion_prices_per_building(name, count, modifier_storage)?;
+        let building = &self.buildings[name];
+        let next_tier = self.next_tier(name).unwrap();
+        let next_tier_name = next_tier.asset().name.clone();
+        let build_ticks = next_tier.asset().build_ticks;
+
+        if !next_tier.is_unlocked() {
+
+            return Err(BuildingConversionError::Locked(next_tier_name));
+
+        }
+
+        if count <= 0 || building.count() < count {
+
+            return Err(BuildingConversionError::NotEnoughBuilding(name.to_string(), count.max(1)));
+
+        }
+
+        if let Some(max_count) = next_tier.max_count().filter(|max_count| next_tier.count() + next_tier.queued_count() + count > *max_count) {
+
+            return Err(BuildingConversionError::LimitReached(next_tier_name, max_count));
+
+        }
+
+        let needed = to_number((next_tier.asset().size.max(0f64) - building.asset().size.max(0f64)) * count as f64);
+        if let Some(free) = free_space.filter(|free| needed > *free) {
+
+            return Err(BuildingConversionError::NotEnoughSpace { needed, free });
+
+        }
+
+        let mut total_prices = ResourceStorage::new();
+        for building_prices in prices.iter() { total_prices.combine(building_prices); }
+
+        if let Some((resource, _)) = total_prices
+            .iter()
+            .find(|(resource, value)| resource_manager.count(resource) < **value) {
+
+            return Err(BuildingConversionError::NotEnoughResource(resource.clone()));
+
+        }
+
+        for (resource, value) in total_prices.iter() {
+
+            resource_manager.add_count(resource, -*value);
+
+        }
+
+        let inactive_count = building.count() - building.active_count();
+        let active_count = count - count.min(inactive_count);
+        let unlocked_productions = building.unlocked_productions().iter().cloned().collect::<Vec<_>>();
+        let active_productions = building.active_productions().iter().cloned().collect::<Vec<_>>();
+
+        let building = self.buildings.get_mut(name).unwrap();
+        building.add_count(-count);
+        building.add_active_count(-active_count);
+        building.calculate_prices(modifier_storage);
+
+        let next_tier = self.buildings.get_mut(&next_tier_name).unwrap();
+        next_tier.inherit_productions(&unlocked_productions, &active_productions);
+
+        if build_ticks > 0 {
+
+            for building_prices in prices.into_iter() {
+
+                self.construction_queue.push(BuildingConstruction {
+                    id: self.next_construction_id,
+                    name: next_tier_name.clone(),
+                    progress: 0,
+                    total_ticks: build_ticks,
+                    prices: building_prices.iter().map(|(resource, value)| (resource.clone(), *value)).collect(),
+                    converted_from: Some(name.to_string()),
+                });
+
+                self.next_construction_id += 1;
+
+            }
+
+            next_tier.add_queued_count(count);
+
+        } else {
+
+            next_tier.add_count(count);
+            next_tier.add_active_count(active_count);
+
+        }
+
+        next_tier.calculate_prices(modifier_storage);
+
+        Ok(())
+
+    }
+
+}
+
+/// Implementations related to building requirements.
+impl BuildingManager {
+
+    /// Unlocks buildings whose requirements are all met.
+    ///
+    /// Returns names of the newly unlocked buildings.
+    pub fn update_unlocks(&mut self, resource_manager: &ResourceManager, upgrade_manager: &UpgradeManager) -> Vec<String> {
+
+        let mut unlocked = self.buildings
+            .iter()
+            .filter(|(_, building)| !building.is_unlocked())
+            .filter(|(_, building)| {
+                building.asset()
+                    .requirements
+                    .iter()
+                    .all(|requirement| self.requirement_progress(requirement, resource_manager, upgrade_manager).0)
+            })
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<_>>();
+
+        for name in unlocked.iter() {
+
+            self.unlock(name);
+
+        }
+
+        unlocked.sort();
+        unlocked
+
+    }
+
+    /// Is the building shown to the player?
+    ///
+    /// Unlocked buildings are always shown, locked ones once their reveal requirements are met.
+    pub fn is_revealed(&self, building: &Building, resource_manager: &ResourceManager, upgrade_manager: &UpgradeManager) -> bool {
+
+        building.is_unlocked() || building.asset()
+            .reveal
+            .iter()
+            .all(|requirement| self.requirement_progress(requirement, resource_manager, upgrade_manager).0)
+
+    }
+
+    /// Returns whether the requirement is met, and the current value it compares if there is one.
+    fn requirement_progress(&self, requirement: &BuildingRequirement, resource_manager: &ResourceManager, upgrade_manager: &UpgradeManager) -> (bool, Option<Number>) {
+
+        match requirement {
+            BuildingRequirement::ResourceSeen { name } => (resource_manager.is_unlocked(name), None),
+            BuildingRequirement::ResourceCount { name, count } => {
+
+                let current = resource_manager.count(name);
+                (current >= *count, Some(current))
+
+            },
+            BuildingRequirement::BuildingCount { name, count } => {
+
+                let current = self.buildings.get(name).map(|b| b.count()).unwrap_or(0);
+                (current >= *count, Some(to_number(current as f64)))
+
+            },
+            BuildingRequirement::Upgrade { name, count } => {
+
+                let current = upgrade_manager.count(name);
+                (current >= *count, Some(to_number(current as f64)))
+
+            },
+        }
+
+    }
+
+    /// Returns requirements of the building that are not met yet.
+    fn unmet_requirements(&self, building: &Building, resource_manager: &ResourceManager, upgrade_manager: &UpgradeManager) -> Vec<BuildingRequirementSnapshot> {
+
+        if building.is_unlocked() { return Vec::new(); }
+
+        building.asset()
+            .requirements
+            .iter()
+            .filter_map(|requirement| {
+
+                let (is_met, current) = self.requirement_progress(requirement, resource_manager, upgrade_manager);
+                if is_met { None } else { Some(BuildingRequirementSnapshot { requirement: requirement.clone(), current }) }
+
+            })
+            .collect()
+
+    }
+
+}
+
+/// Implementations related to snapshots.
+impl BuildingManager {
+
+    /// Is the building an obsolete tier that should be hidden?
+    fn is_hidden_obsolete(&self, building: &Building) -> bool {
+
+        building.count() <= 0 &&
+            building.asset().upgrades_into.as_ref().is_some_and(|tier| tier.hide_obsolete) &&
+            self.is_obsolete(&building.asset().name)
+
+    }
+
+    /// Creates a snapshot of the construction queue.
+    pub fn construction_snapshot(&self) -> BuildingConstructionQueueSnapshot {
+
+        BuildingConstructionQueueSnapshot::from(self)
+
+    }
+
+    /// Creates snapshots of shown buildings, sorted by name.
+    ///
+    /// Locked buildings list their unmet requirements, and buildings that are not revealed yet are hidden.
+    /// Obsolete tiers are hidden once there is none left, unless the asset keeps them.
+    pub fn snapshot(&self, resource_manager: &ResourceManager, upgrade_manager: &UpgradeManager, modifier_storage: &ModifierStorage) -> Vec<BuildingSnapshot> {
+
+        let mut snapshots = self.buildings
+            .values()
+            .filter(|b| self.is_revealed(b, resource_manager, upgrade_manager))
+            .filter(|b| !self.is_hidden_obsolete(b))
+            .map(|b| {
+
+                let mut snapshot = BuildingSnapshot::from(b);
+                snapshot.unmet_requirements = self.unmet_requirements(b, resource_manager, upgrade_manager);
+                snapshot.is_obsolete = self.is_obsolete(&snapshot.name);
+                snapshot.conversion = self.conversion_prices(&snapshot.name, 1, modifier_storage)
+                    .ok()
+                    .map(|prices| BuildingConversionSnapshot {
+                        name: b.asset().upgrades_into.as_ref().unwrap().name.clone(),
+                        prices: ResourceValueSnapshot::from_storage(&prices),
+                    });
+                snapshot
+
+            })
+            .collect::<Vec<_>>();
+
+        snapshots.sort_by(|a, b| a.name.cmp(&b.name));
+        snapshots
+
+    }
+
+}
+
+/// Implementations related to saves.
+impl BuildingManager {
+
+    /// Creates saves of buildings, sorted by name.
+    pub fn save(&self) -> Vec<BuildingSave> {
+
+        let mut saves = self.buildings
+            .values()
+            .map(BuildingSave::from)
+            .collect::<Vec<_>>();
+
+        saves.sort_by(|a, b| a.name.cmp(&b.name));
+        saves
+
+    }
+
+    /// Creates a save of the construction queue.
+    pub fn save_construction(&self) -> Vec<BuildingConstruction> {
+
+        self.construction_queue.clone()
+
+    }
+
+    /// Restores the construction queue from a save, replacing the current one.
+    ///
+    /// Constructions of buildings that do not exist are ignored.
+    pub fn load_construction_save(&mut self, saves: &[BuildingConstruction], modifier_storage: &ModifierStorage) {
+
+        self.construction_queue = saves
+            .iter()
+            .filter(|save| self.buildings.contains_key(&save.name))
+            .cloned()
+            .collect();
+
+        self.next_construction_id = self.construction_queue
+            .iter()
+            .map(|construction| construction.id + 1)
+            .max()
+            .unwrap_or(0);
+
+        for building in self.buildings.values_mut() {
+
+            building.set_queued_count(0);
+
+        }
+
+        for construction in self.construction_queue.iter() {
+
+            if let Some(building) = self.buildings.get_mut(&construction.name) { building.add_queued_count(1); }
+
+        }
+
+        for building in self.buildings.values_mut() {
+
+            building.calculate_prices(modifier_storage);
+
+        }
+
+    }
+
+    /// Restores buildings from saves.
+    ///
+    /// Saves of buildings that do not exist are ignored.
+    pub fn load_save(&mut self, saves: &[BuildingSave]) {
+
+        for save in saves.iter() {
+
+            let Some(building) = self.buildings.get_mut(&save.name) else { continue };
+
+            building.set_count(save.count);
+            building.set_active_count(save.active_count.min(save.count));
+            building.set_workers(save.workers);
+            save.unlocked_productions.iter().for_each(|p| building.unlock_production(p));
+            if !save.active_productions.is_empty() { building.restore_productions(&save.active_productions, save.switching_productions.clone()); }
+            if save.is_unlocked { building.unlock(); }
+
+        }
+
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{BuildingManager, BuildingProductionError, BuildingPurchaseError, BuildingWorkerError};
+    use crate::core::number::{to_f64, to_number};
+    use crate::core::thing::modifier::{ModifierCalculationMethod, ModifierEntry, ModifierStorage};
+    use crate::core::thing::resource::{ResourceManager, ResourceStorage};
+    use crate::core::thing::upgrade::{UpgradeManager, UpgradeSave};
+
+    /// Creates a JSON building asset that stores 10 wood and generates the modifiers.
+    fn building(name: &str, category: &str, modifiers: &[(&str, f64, &str)]) -> String {
+
+        let modifiers = modifiers
+            .iter()
+            .map(|(name, value, calculation)| format!(r#"{{"name":"{name}","value":{value},"calculation":"{calculation}"}}"#))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        format!(r#"{{"name":"{name}","category":"{category}","prices":[],"price_multiplier":1,"size":0,"productions":[{{"name":"default","upkeeps":[],"outputs":[],"modifiers":[{modifiers}],"storages":[{{"name":"wood","value":10}}]}}]}}"#)
+
+    }
+
+    /// Creates a JSON building asset that upkeeps and outputs the resources.
+    fn production_building(name: &str, upkeeps: &[(&str, f64)], outputs: &[(&str, f64)]) -> String {
+
+        let values = |values: &[(&str, f64)]| values
+            .iter()
+            .map(|(name, value)| format!(r#"{{"name":"{name}","value":{value}}}"#))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        format!(r#"{{"name":"{name}","category":"production","prices":[],"price_multiplier":1,"size":0,"productions":[{{"name":"default","upkeeps":[{}],"outputs":[{}],"modifiers":[],"storages":[]}}]}}"#, values(upkeeps), values(outputs))
+
+    }
+
+    /// Creates a resource manager with stockpiled wood and power that was supplied and demanded in the last tick.
+    fn resource_manager() -> ResourceManager {
+
+        let mut resource_manager = ResourceManager::new();
+        resource_manager.load_from_str(r#"{"name":"wood","category":"raw","base_capacity":100,"modifiers":[]}"#).unwrap();
+        resource_manager.load_from_str(r#"{"name":"power","category":"energy","kind":"flow","base_capacity":0,"modifiers":[]}"#).unwrap();
+
+        let mut power = ResourceStorage::new();
+        power.add("power".to_string(), to_number(1f64));
+        resource_manager.set_production(&power);
+        resource_manager.set_consumption(&power);
+
+        resource_manager
+
+    }
+
+    fn manager(buildings: &[String]) -> BuildingManager {
+
+        manager_with(buildings, &ResourceManager::new())
+
+    }
+
+    fn manager_with(buildings: &[String], resource_manager: &ResourceManager) -> BuildingManager {
+
+        let mut manager = BuildingManager::new();
+
+        for asset in buildings.iter() {
+
+            manager.load_from_str(asset).unwrap();
+
+        }
+
+        for building in manager.buildings.values_mut() {
+
+            building.set_count(1);
+            building.set_active_count(1);
+
+        }
+
+        manager.calculate(&ModifierStorage::new(), resource_manager);
+        manager
+
+    }
+
+    fn storage(manager: &BuildingManager, name: &str) -> f64 {
+
+        to_f64(manager.get(name).unwrap().calculated_storages().value("wood"))
+
+    }
+
+    #[test]
+    fn modifiers_on_itself_apply_within_the_tick() {
+
+        let manager = manager(&[building("storage", "storage", &[("building.category.storage.storage", 1f64, "flat")])]);
+
+        assert_eq!(storage(&manager, "storage"), 11f64);
+        assert_eq!(manager.calculation_cycles(), &[vec!["storage".to_string()]]);
+        assert!(manager.unresolved_cycles().is_empty());
+
+    }
+
+    #[test]
+    fn chained_modifiers_apply_within_the_tick() {
+
+        let manager = manager(&[
+            building("c", "c", &[]),
+            building("b", "b", &[("building.name.c.storage", 1f64, "additive")]),
+            building("a", "a", &[("building.name.b.storage", 5f64, "base")]),
+        ]);
+
+        assert_eq!(storage(&manager, "a"), 10f64);
+        assert_eq!(storage(&manager, "b"), 15f64);
+        assert_eq!(storage(&manager, "c"), 20f64);
+        assert!(manager.calculation_cycles().is_empty());
+
+    }
+
+    #[test]
+    fn cyclic_modifiers_are_resolved_and_reported() {
+
+        let manager = manager(&[
+            building("a", "a", &[("building.name.b.storage", 1f64, "flat")]),
+            building("b", "b", &[("building.name.a.storage", 2f64, "flat")]),
+        ]);
+
+        assert_eq!(storage(&manager, "a"), 12f64);
+        assert_eq!(storage(&manager, "b"), 11f64);
+        assert_eq!(manager.calculation_cycles(), &[vec!["a".to_string(), "b".to_string()]]);
+        assert!(manager.unresolved_cycles().is_empty());
+
+    }
+
+
+    #[test]
+    fn flow_shortage_throttles_consumers() {
+
+        let manager = manager_with(&[
+            production_building("generator", &[], &[("power", 5f64)]),
+            production_building("saw", &[("power", 5f64)], &[("wood", 2f64)]),
+            production_building("mill", &[("power", 5f64)], &[("wood", 4f64)]),
+        ], &resource_manager());
+
+        assert_eq!(manager.get("saw").unwrap().throttle(), 0.5f64);
+        assert_eq!(manager.get("mill").unwrap().throttle(), 0.5f64);
+        assert_eq!(manager.get("generator").unwrap().throttle(), 1f64);
+        assert_eq!(to_f64(manager.calculated_outputs().value("wood")), 3f64);
+        assert_eq!(to_f64(manager.calculated_outputs().value("power")), 5f64);
+
+    }
+
+    #[test]
+    fn enough_flow_does_not_throttle() {
+
+        let manager = manager_with(&[
+            production_building("generator", &[], &[("power", 10f64)]),
+            production_building("saw", &[("power", 5f64)], &[("wood", 2f64)]),
+        ], &resource_manager());
+
+        assert_eq!(manager.get("saw").unwrap().throttle(), 1f64);
+        assert_eq!(to_f64(manager.calculated_outputs().value("wood")), 2f64);
+
+    }
+
+    /// Sets production and consumption of the last tick, resources are empty.
+    fn set_rates(resource_manager: &mut ResourceManager, production: &[(&str, f64)], consumption: &[(&str, f64)]) {
+
+        let storage = |values: &[(&str, f64)]| {
+
+            let mut storage = ResourceStorage::new();
+            for (name, value) in values.iter() { storage.add(name.to_string(), to_number(*value)); }
+            storage
+
+        };
+
+        resource_manager.set_production(&storage(production));
+        resource_manager.set_consumption(&storage(consumption));
+
+    }
+
+    #[test]
+    fn shut_down_buildings_do_not_consume_or_throttle() {
+
+        let mut resource_manager = resource_manager();
+        set_rates(&mut resource_manager, &[("power", 1f64)], &[("power", 1f64), ("wood", 1f64)]);
+
+        let manager = manager_with(&[
+            production_building("generator", &[], &[("power", 5f64)]),
+            production_building("saw", &[("power", 5f64)], &[("wood", 2f64)]),
+            production_building("kiln", &[("power", 5f64), ("wood", 1f64)], &[]),
+        ], &resource_manager);
+
+        assert!(manager.get("kiln").unwrap().shutdown().is_some());
+        assert_eq!(to_f64(manager.calculated_upkeeps().value("power")), 5f64);
+        assert_eq!(to_f64(manager.calculated_upkeeps().value("wood")), 0f64);
+        assert_eq!(manager.get("saw").unwrap().throttle(), 1f64);
+
+    }
+
+    #[test]
+    fn shut_down_building_restarts_only_if_its_own_upkeep_is_covered() {
+
+        let mut resource_manager = resource_manager();
+        set_rates(&mut resource_manager, &[], &[("wood", 3f64)]);
+
+        let mut manager = manager_with(&[
+            production_building("small_kiln", &[("wood", 1f64)], &[]),
+            production_building("big_kiln", &[("wood", 2f64)], &[]),
+        ], &resource_manager);
+
+        assert!(manager.get("small_kiln").unwrap().shutdown().is_some());
+        assert!(manager.get("big_kiln").unwrap().shutdown().is_some());
+
+        set_rates(&mut resource_manager, &[("wood", 1f64)], &[]);
+        manager.calculate(&ModifierStorage::new(), &resource_manager);
+
+        assert!(manager.get("small_kiln").unwrap().shutdown().is_none());
+        assert!(manager.get("big_kiln").unwrap().shutdown().is_some());
+        assert_eq!(to_f64(manager.calculated_upkeeps().value("wood")), 1f64);
+
+    }
+
+    /// Creates a manager with 2 huts that upgrade into houses, and resources with 1000 wood.
+    fn tier_manager(tier_prices: &str, build_ticks: u32) -> (BuildingManager, ResourceManager) {
+
+        let mut manager = BuildingManager::new();
+        manager.load_from_str(&format!(r#"{{"name":"hut","category":"housing","prices":[{{"name":"wood","value":10}}],"price_multiplier":2,"size":0,"productions":[],"upgrades_into":{{"name":"house","prices":[{tier_prices}]}}}}"#)).unwrap();
+        manager.load_from_str(&format!(r#"{{"name":"house","category":"housing","prices":[{{"name":"wood","value":100}}],"price_multiplier":2,"size":0,"build_ticks":{build_ticks},"productions":[]}}"#)).unwrap();
+
+        let hut = manager.buildings.get_mut("hut").unwrap();
+        hut.set_count(2);
+        hut.set_active_count(2);
+        manager.buildings.get_mut("house").unwrap().unlock();
+
+        let mut resource_manager = ResourceManager::new();
+        resource_manager.load_from_str(r#"{"name":"wood","category":"raw","base_capacity":1000,"modifiers":[]}"#).unwrap();
+        resource_manager.add_count("wood", to_number(1000f64));
+
+        (manager, resource_manager)
+
+    }
+
+    #[test]
+    fn conversion_prices_continue_from_queued_buildings() {
+
+        let (mut manager, _) = tier_manager("", 0);
+        let house = manager.buildings.get_mut("house").unwrap();
+        house.set_count(1);
+        house.set_queued_count(1);
+
+        // The third house costs 400 wood, and the second hut cost 20 wood.
+        assert_eq!(to_f64(manager.conversion_prices("hut", 1, &ModifierStorage::new()).unwrap().value("wood")), 380f64);
+
+    }
+
+    #[test]
+    fn explicit_conversion_prices_apply_price_modifiers() {
+
+        let (manager, _) = tier_manager(r#"{"name":"wood","value":50}"#, 0);
+
+        let mut modifier_storage = ModifierStorage::new();
+        modifier_storage.add(ModifierEntry::new("building.name.house.price".to_string(), to_number(-0.5), ModifierCalculationMethod::Additive));
+
+        assert_eq!(to_f64(manager.conversion_prices("hut", 2, &ModifierStorage::new()).unwrap().value("wood")), 100f64);
+        assert_eq!(to_f64(manager.conversion_prices("hut", 2, &modifier_storage).unwrap().value("wood")), 50f64);
+
+    }
+
+    #[test]
+    fn conversion_is_queued_when_the_next_tier_takes_time_to_build() {
+
+        let (mut manager, mut resource_manager) = tier_manager(r#"{"name":"wood","value":50}"#, 2);
+        let modifier_storage = ModifierStorage::new();
+
+        manager.convert("hut", 1, None, &mut resource_manager, &modifier_storage).unwrap();
+
+        assert_eq!(manager.get("hut").unwrap().count(), 1);
+        assert_eq!(manager.get("house").unwrap().count(), 0);
+        assert_eq!(manager.get("house").unwrap().queued_count(), 1);
+        assert_eq!(manager.construction_queue()[0].converted_from.as_deref(), Some("hut"));
+        assert!((to_f64(resource_manager.count("wood")) - 950f64).abs() < 1e-9);
+
+        manager.advance_construction(&modifier_storage);
+        assert_eq!(manager.advance_construction(&modifier_storage), vec!["house".to_string()]);
+        assert_eq!(manager.get("house").unwrap().count(), 1);
+        assert_eq!(manager.get("house").unwrap().active_count(), 1);
+
+    }
+
+    #[test]
+    fn cancelled_conversion_gives_back_the_converted_building() {
+
+        let (mut manager, mut resource_manager) = tier_manager(r#"{"name":"wood","value":50}"#, 2);
+        let modifier_storage = ModifierStorage::new();
+
+        manager.convert("hut", 1, None, &mut resource_manager, &modifier_storage).unwrap();
+        let id = manager.construction_queue()[0].id;
+        manager.cancel_construction(id, &mut resource_manager, &modifier_storage).unwrap();
+
+        assert_eq!(manager.get("hut").unwrap().count(), 2);
+        assert_eq!(manager.get("house").unwrap().queued_count(), 0);
+        assert!((to_f64(resource_manager.count("wood")) - 1000f64).abs() < 1e-9);
+
+    }
+
+    /// Creates kilns that burn wood by default, or switch into making charcoal for 2 wood per kiln and 2 ticks.
+    fn kiln_manager(resource_manager: &ResourceManager) -> BuildingManager {
+
+        let mut manager = manager_with(&[r#"{"name":"kiln","category":"production","prices":[],"price_multiplier":1,"size":0,"productions":[
+            {"name":"default","exclusive_group":"fire","upkeeps":[],"outputs":[{"name":"wood","value":1}],"modifiers":[],"storages":[]},
+            {"name":"charcoal","exclusive_group":"fire","switch_prices":[{"name":"wood","value":2}],"switch_ticks":2,"upkeeps":[],"outputs":[{"name":"wood","value":5}],"modifiers":[],"storages":[]}]}"#.to_string()], resource_manager);
+
+        manager.set_count("kiln", 3);
+        manager.set_active_count("kiln", 3);
+        manager.unlock_production("kiln", "charcoal");
+
+        manager
+
+    }
+
+    fn is_active(manager: &BuildingManager, production: &str) -> bool {
+
+        manager.get("kiln").unwrap().is_production_active(production)
+
+    }
+
+    #[test]
+    fn exclusive_productions_replace_each_other() {
+
+        let mut resource_manager = resource_manager();
+        resource_manager.add_count("wood", to_number(100f64));
+        let mut manager = kiln_manager(&resource_manager);
+
+        manager.set_active_production("kiln", "charcoal", true, &mut resource_manager).unwrap();
+        assert!(is_active(&manager, "charcoal"));
+        assert!(!is_active(&manager, "default"));
+
+        manager.set_active_production("kiln", "default", true, &mut resource_manager).unwrap();
+        assert!(is_active(&manager, "default"));
+        assert!(!is_active(&manager, "charcoal"));
+
+        assert!(matches!(manager.set_active_production("kiln", "default", false, &mut resource_manager), Err(BuildingProductionError::DefaultProduction(_))));
+        assert!(is_active(&manager, "default"));
+
+    }
+
+    #[test]
+    fn deactivating_the_last_production_in_the_group_activates_default() {
+
+        let mut resource_manager = resource_manager();
+        resource_manager.add_count("wood", to_number(100f64));
+        let mut manager = kiln_manager(&resource_manager);
+
+        manager.set_active_production("kiln", "charcoal", true, &mut resource_manager).unwrap();
+        manager.set_active_production("kiln", "charcoal", false, &mut resource_manager).unwrap();
+
+        assert!(is_active(&manager, "default"));
+        assert!(!is_active(&manager, "charcoal"));
+
+    }
+
+    #[test]
+    fn switch_price_is_paid_for_every_building() {
+
+        let mut resource_manager = resource_manager();
+        resource_manager.add_count("wood", to_number(5f64));
+        let mut manager = kiln_manager(&resource_manager);
+
+        assert!(matches!(manager.set_active_production("kiln", "charcoal", true, &mut resource_manager), Err(BuildingProductionError::NotEnoughResource(name)) if name == "wood"));
+        assert!(!is_active(&manager, "charcoal"));
+        assert_eq!(to_f64(resource_manager.count("wood")), 5f64);
+
+        resource_manager.add_count("wood", to_number(1f64));
+        manager.set_active_production("kiln", "charcoal", true, &mut resource_manager).unwrap();
+        assert!(to_f64(resource_manager.count("wood")).abs() < 1e-9);
+
+        // Already active productions are not paid for again.
+        manager.set_active_production("kiln", "charcoal", true, &mut resource_manager).unwrap();
+        assert!(to_f64(resource_manager.count("wood")).abs() < 1e-9);
+
+    }
+
+    #[test]
+    fn switched_production_works_after_its_delay() {
+
+        let mut resource_manager = resource_manager();
+        resource_manager.add_count("wood", to_number(100f64));
+        let mut manager = kiln_manager(&resource_manager);
+
+        manager.calculate(&ModifierStorage::new(), &resource_manager);
+        assert_eq!(to_f64(manager.calculated_outputs().value("wood")), 3f64);
+
+        manager.set_active_production("kiln", "charcoal", true, &mut resource_manager).unwrap();
+        assert_eq!(manager.get("kiln").unwrap().production_switch_ticks("charcoal"), 2);
+
+        manager.calculate(&ModifierStorage::new(), &resource_manager);
+        assert_eq!(to_f64(manager.calculated_outputs().value("wood")), 0f64);
+        assert_eq!(manager.get("kiln").unwrap().production_switch_ticks("charcoal"), 1);
+
+        manager.calculate(&ModifierStorage::new(), &resource_manager);
+        assert_eq!(to_f64(manager.calculated_outputs().value("wood")), 0f64);
+        assert_eq!(manager.get("kiln").unwrap().production_switch_ticks("charcoal"), 0);
+
+        manager.calculate(&ModifierStorage::new(), &resource_manager);
+        assert_eq!(to_f64(manager.calculated_outputs().value("wood")), 15f64);
+
+    }
+
+    #[test]
+    fn switching_productions_are_restored_from_a_save() {
+
+        let mut resource_manager = resource_manager();
+        resource_manager.add_count("wood", to_number(100f64));
+        let mut manager = kiln_manager(&resource_manager);
+
+        manager.set_active_production("kiln", "charcoal", true, &mut resource_manager).unwrap();
+        manager.calculate(&ModifierStorage::new(), &resource_manager);
+
+        let save = manager.save();
+        assert_eq!(save[0].switching_productions.get("charcoal"), Some(&1));
+
+        let mut restored = manager_with(&[], &resource_manager);
+        restored.load_from_str(r#"{"name":"kiln","category":"production","prices":[],"price_multiplier":1,"size":0,"productions":[
+            {"name":"default","exclusive_group":"fire","upkeeps":[],"outputs":[],"modifiers":[],"storages":[]},
+            {"name":"charcoal","exclusive_group":"fire","switch_ticks":2,"upkeeps":[],"outputs":[],"modifiers":[],"storages":[]}]}"#).unwrap();
+        restored.load_save(&save);
+
+        assert!(is_active(&restored, "charcoal"));
+        assert!(!is_active(&restored, "default"));
+        assert_eq!(restored.get("kiln").unwrap().production_switch_ticks("charcoal"), 1);
+
+    }
+
+    /// Creates huts, a forge that needs every kind of requirement and is revealed at 10 wood, and a tower that is never revealed.
+    fn requirement_manager() -> BuildingManager {
+
+        let mut manager = BuildingManager::new();
+        manager.load_from_str(r#"{"name":"hut","category":"housing","prices":[],"price_multiplier":1,"size":0,"productions":[]}"#).unwrap();
+        manager.load_from_str(r#"{"name":"forge","category":"industry","prices":[],"price_multiplier":1,"size":0,"productions":[],
+            "requirements":[{"type":"resource_seen","name":"iron"},{"type":"resource_count","name":"wood","count":50},{"type":"building_count","name":"hut","count":2},{"type":"upgrade","name":"smithing"}],
+            "reveal":[{"type":"resource_count","name":"wood","count":10}]}"#).unwrap();
+        manager.load_from_str(r#"{"name":"tower","category":"defense","prices":[],"price_multiplier":1,"size":0,"productions":[],
+            "requirements":[{"type":"upgrade","name":"smithing","count":2}],
+            "reveal":[{"type":"upgrade","name":"smithing","count":5}]}"#).unwrap();
+
+        manager
+
+    }
+
+    /// Returns names of the unmet requirement kinds of the shown building, `None` if it is not shown.
+    fn unmet_requirements(manager: &BuildingManager, resource_manager: &ResourceManager, upgrade_manager: &UpgradeManager, name: &str) -> Option<Vec<String>> {
+
+        manager.snapshot(resource_manager, upgrade_manager, &ModifierStorage::new())
+            .into_iter()
+            .find(|snapshot| snapshot.name == name)
+            .map(|snapshot| snapshot.unmet_requirements
+                .iter()
+                .map(|unmet| serde_json::to_value(unmet).unwrap()["type"].as_str().unwrap().to_string())
+                .collect())
+
+    }
+
+    #[test]
+    fn buildings_unlock_once_every_requirement_is_met() {
+
+        let mut manager = requirement_manager();
+        let mut resource_manager = resource_manager();
+        resource_manager.load_from_str(r#"{"name":"iron","category":"raw","base_capacity":10,"modifiers":[]}"#).unwrap();
+        let mut upgrade_manager = UpgradeManager::new();
+        upgrade_manager.load_from_str(r#"{"name":"smithing","category":"tools","price":[],"price_multiplier":1,"max_level":null,"unlocks":[],"modifiers":[]}"#).unwrap();
+
+        assert_eq!(manager.update_unlocks(&resource_manager, &upgrade_manager), vec!["hut".to_string()]);
+
+        resource_manager.add_count("wood", to_number(50f64));
+        assert!(manager.update_unlocks(&resource_manager, &upgrade_manager).is_empty());
+
+        manager.set_count("hut", 2);
+        assert!(manager.update_unlocks(&resource_manager, &upgrade_manager).is_empty());
+
+        upgrade_manager.load_save(&[UpgradeSave { name: "smithing".to_string(), count: 1, is_unlocked: true }]);
+        assert!(manager.update_unlocks(&resource_manager, &upgrade_manager).is_empty());
+        assert_eq!(unmet_requirements(&manager, &resource_manager, &upgrade_manager, "forge"), Some(vec!["resource_seen".to_string()]));
+
+        resource_manager.add_count("iron", to_number(1f64));
+        resource_manager.update_unlocks(&manager, &upgrade_manager);
+        assert_eq!(manager.update_unlocks(&resource_manager, &upgrade_manager), vec!["forge".to_string()]);
+        assert!(!manager.get("tower").unwrap().is_unlocked());
+
+        upgrade_manager.load_save(&[UpgradeSave { name: "smithing".to_string(), count: 2, is_unlocked: true }]);
+        assert_eq!(manager.update_unlocks(&resource_manager, &upgrade_manager), vec!["tower".to_string()]);
+
+    }
+
+    #[test]
+    fn unmet_requirements_are_listed_for_revealed_locked_buildings() {
+
+        let mut manager = requirement_manager();
+        let mut resource_manager = resource_manager();
+        resource_manager.load_from_str(r#"{"name":"iron","category":"raw","base_capacity":10,"modifiers":[]}"#).unwrap();
+        let upgrade_manager = UpgradeManager::new();
+        manager.update_unlocks(&resource_manager, &upgrade_manager);
+
+        assert_eq!(unmet_requirements(&manager, &resource_manager, &upgrade_manager, "forge"), None);
+        assert_eq!(unmet_requirements(&manager, &resource_manager, &upgrade_manager, "tower"), None);
+        assert_eq!(unmet_requirements(&manager, &resource_manager, &upgrade_manager, "hut"), Some(Vec::new()));
+
+        resource_manager.add_count("wood", to_number(10f64));
+        manager.set_count("hut", 1);
+
+        let snapshot = manager.snapshot(&resource_manager, &upgrade_manager, &ModifierStorage::new());
+        let forge = snapshot.iter().find(|snapshot| snapshot.name == "forge").unwrap();
+        let current = forge.unmet_requirements.iter().map(|unmet| unmet.current.map(to_f64)).collect::<Vec<_>>();
+        assert_eq!(unmet_requirements(&manager, &resource_manager, &upgrade_manager, "forge"), Some(vec!["resource_seen".to_string(), "resource_count".to_string(), "building_count".to_string(), "upgrade".to_string()]));
+        assert_eq!(current, vec![None, Some(10f64), Some(1f64), Some(0f64)]);
+
+        manager.unlock("forge");
+        assert_eq!(unmet_requirements(&manager, &resource_manager, &upgrade_manager, "forge"), Some(Vec::new()));
+
+    }
+
+    /// Creates an unlocked, free building with the extra JSON fields.
+    fn limited_manager(fields: &str, modifier_storage: &ModifierStorage) -> BuildingManager {
+
+        let mut manager = BuildingManager::new();
+        manager.load_from_str(&format!(r#"{{"name":"tower","category":"defense","prices":[],"price_multiplier":1,"size":0,"productions":[],{fields}}}"#)).unwrap();
+        manager.unlock("tower");
+        manager.calculate(modifier_storage, &ResourceManager::new());
+
+        manager
+
+    }
+
+    fn snapshot_max_count(manager: &BuildingManager) -> Option<i32> {
+
+        manager.snapshot(&ResourceManager::new(), &UpgradeManager::new(), &ModifierStorage::new())[0].max_count
+
+    }
+
+    #[test]
+    fn unique_buildings_are_limited_to_one() {
+
+        let mut manager = limited_manager(r#""unique":true,"max_count":5"#, &ModifierStorage::new());
+        let mut resource_manager = ResourceManager::new();
+
+        manager.purchase("tower", None, &mut resource_manager, &ModifierStorage::new()).unwrap();
+
+        assert!(matches!(manager.purchase("tower", None, &mut resource_manager, &ModifierStorage::new()), Err(BuildingPurchaseError::LimitReached(_, 1))));
+        assert_eq!(manager.get("tower").unwrap().count(), 1);
+        assert_eq!(snapshot_max_count(&manager), Some(1));
+
+    }
+
+    #[test]
+    fn max_count_is_modified_by_building_modifiers() {
+
+        let mut modifier_storage = ModifierStorage::new();
+        modifier_storage.add(ModifierEntry::new("building.name.tower.max_count".to_string(), to_number(1f64), ModifierCalculationMethod::Base));
+        modifier_storage.add(ModifierEntry::new("building.name.tower.max_count".to_string(), to_number(0.5), ModifierCalculationMethod::Additive));
+
+        let manager = limited_manager(r#""max_count":2"#, &modifier_storage);
+        assert_eq!(manager.get("tower").unwrap().max_count(), Some(4));
+        assert_eq!(snapshot_max_count(&manager), Some(4));
+
+        let manager = limited_manager(r#""build_ticks":0"#, &modifier_storage);
+        assert_eq!(snapshot_max_count(&manager), None);
+
+    }
+
+    #[test]
+    fn queued_buildings_count_toward_the_limit() {
+
+        let mut manager = limited_manager(r#""max_count":2,"build_ticks":3"#, &ModifierStorage::new());
+        let mut resource_manager = ResourceManager::new();
+
+        manager.purchase("tower", None, &mut resource_manager, &ModifierStorage::new()).unwrap();
+        manager.purchase("tower", None, &mut resource_manager, &ModifierStorage::new()).unwrap();
+
+        assert!(matches!(manager.purchase("tower", None, &mut resource_manager, &ModifierStorage::new()), Err(BuildingPurchaseError::LimitReached(_, 2))));
+        assert_eq!(manager.get("tower").unwrap().count(), 0);
+        assert_eq!(manager.get("tower").unwrap().queued_count(), 2);
+
+    }
+
+    /// Creates mills that cost 10 wood, doubled for every mill, and take 2 ticks to build, and 1000 wood.
+    fn construction_manager() -> (BuildingManager, ResourceManager) {
+
+        let mut resource_manager = ResourceManager::new();
+        resource_manager.load_from_str(r#"{"name":"wood","category":"raw","base_capacity":1000,"modifiers":[]}"#).unwrap();
+        resource_manager.add_count("wood", to_number(1000f64));
+
+        let mut manager = BuildingManager::new();
+        manager.load_from_str(r#"{"name":"mill","category":"industry","prices":[{"name":"wood","value":10}],"price_multiplier":2,"size":0,"build_ticks":2,"productions":[]}"#).unwrap();
+        manager.unlock("mill");
+        manager.calculate(&ModifierStorage::new(), &resource_manager);
+
+        (manager, resource_manager)
+
+    }
+
+    fn progress(manager: &BuildingManager) -> Vec<u32> {
+
+        manager.construction_queue().iter().map(|construction| construction.progress).collect()
+
+    }
+
+    #[test]
+    fn build_slots_construct_buildings_in_parallel() {
+
+        let (mut manager, mut resource_manager) = construction_manager();
+        let mut modifier_storage = ModifierStorage::new();
+        modifier_storage.add(ModifierEntry::new("building.global.build_slots".to_string(), to_number(1f64), ModifierCalculationMethod::Flat));
+
+        for _ in 0..3 { manager.purchase("mill", None, &mut resource_manager, &modifier_storage).unwrap(); }
+
+        assert!(manager.advance_construction(&modifier_storage).is_empty());
+        assert_eq!(manager.build_slots(), 2);
+        assert_eq!(progress(&manager), vec![1, 1, 0]);
+
+        assert_eq!(manager.advance_construction(&modifier_storage), vec!["mill".to_string(), "mill".to_string()]);
+        assert_eq!(progress(&manager), vec![0]);
+        assert_eq!((manager.get("mill").unwrap().count(), manager.get("mill").unwrap().active_count(), manager.get("mill").unwrap().queued_count()), (2, 2, 1));
+
+    }
+
+    #[test]
+    fn one_build_slot_constructs_buildings_in_order() {
+
+        let (mut manager, mut resource_manager) = construction_manager();
+        let modifier_storage = ModifierStorage::new();
+
+        manager.purchase("mill", None, &mut resource_manager, &modifier_storage).unwrap();
+        manager.purchase("mill", None, &mut resource_manager, &modifier_storage).unwrap();
+
+        manager.advance_construction(&modifier_storage);
+        assert_eq!(manager.build_slots(), 1);
+        assert_eq!(progress(&manager), vec![1, 0]);
+
+        assert_eq!(manager.advance_construction(&modifier_storage), vec!["mill".to_string()]);
+        assert_eq!(progress(&manager), vec![0]);
+
+        let snapshot = manager.construction_snapshot();
+        assert_eq!(snapshot.slots, 1);
+        assert!(snapshot.queue[0].is_in_progress);
+
+        manager.advance_construction(&modifier_storage);
+        let snapshot = manager.construction_snapshot();
+        assert_eq!((snapshot.queue[0].progress, snapshot.queue[0].total_ticks, snapshot.queue[0].ratio), (1, 2, 0.5));
+
+    }
+
+    #[test]
+    fn cancelled_construction_refunds_its_price() {
+
+        let (mut manager, mut resource_manager) = construction_manager();
+        let modifier_storage = ModifierStorage::new();
+
+        manager.purchase("mill", None, &mut resource_manager, &modifier_storage).unwrap();
+        manager.purchase("mill", None, &mut resource_manager, &modifier_storage).unwrap();
+        assert!((to_f64(resource_manager.count("wood")) - 970f64).abs() < 1e-9);
+
+        let snapshot = manager.construction_snapshot();
+        assert!(!snapshot.queue[1].is_in_progress);
+        assert!((to_f64(snapshot.queue[1].refund[0].value) - 20f64).abs() < 1e-9);
+
+        let id = manager.construction_queue()[1].id;
+        manager.cancel_construction(id, &mut resource_manager, &modifier_storage).unwrap();
+
+        assert!((to_f64(resource_manager.count("wood")) - 990f64).abs() < 1e-9);
+        assert_eq!(manager.get("mill").unwrap().queued_count(), 1);
+        assert!((to_f64(manager.get("mill").unwrap().calculated_prices().value("wood")) - 20f64).abs() < 1e-9);
+        assert!(manager.cancel_construction(id, &mut resource_manager, &modifier_storage).is_err());
+
+    }
+
+    #[test]
+    fn construction_queue_is_restored_from_a_save() {
+
+        let (mut manager, mut resource_manager) = construction_manager();
+        let modifier_storage = ModifierStorage::new();
+
+        manager.purchase("mill", None, &mut resource_manager, &modifier_storage).unwrap();
+        manager.purchase("mill", None, &mut resource_manager, &modifier_storage).unwrap();
+        manager.advance_construction(&modifier_storage);
+
+        let save = serde_json::to_string(&manager.save_construction()).unwrap();
+        let (mut restored, mut resource_manager) = construction_manager();
+        restored.load_construction_save(&serde_json::from_str::<Vec<_>>(&save).unwrap(), &modifier_storage);
+
+        assert_eq!(progress(&restored), vec![1, 0]);
+        assert_eq!(restored.get("mill").unwrap().queued_count(), 2);
+        assert!((to_f64(restored.get("mill").unwrap().calculated_prices().value("wood")) - 40f64).abs() < 1e-9);
+
+        restored.purchase("mill", None, &mut resource_manager, &modifier_storage).unwrap();
+        let ids = restored.construction_queue().iter().map(|construction| construction.id).collect::<Vec<_>>();
+        assert_eq!(ids, vec![0, 1, 2]);
+
+        assert_eq!(restored.advance_construction(&modifier_storage), vec!["mill".to_string()]);
+        assert_eq!(restored.get("mill").unwrap().count(), 1);
+
+    }
+
+    /// Creates a farm with 2 jobs that outputs 4 grain, a mine with 1 job, and a well without jobs that outputs 1 grain.
+    fn job_manager(count: i32) -> BuildingManager {
+
+        let mut manager = manager(&[
+            r#"{"name":"farm","category":"food","prices":[],"price_multiplier":1,"size":0,"productions":[{"name":"default","jobs":2,"upkeeps":[],"outputs":[{"name":"grain","value":4}],"modifiers":[],"storages":[]}]}"#.to_string(),
+            r#"{"name":"mine","category":"industry","prices":[],"price_multiplier":1,"size":0,"productions":[{"name":"default","jobs":1,"upkeeps":[],"outputs":[],"modifiers":[],"storages":[]}]}"#.to_string(),
+            production_building("well", &[], &[("grain", 1f64)]),
+        ]);
+
+        for name in ["farm", "mine", "well"] {
+
+            manager.set_count(name, count);
+            manager.set_active_count(name, count);
+
+        }
+
+        manager
+
+    }
+
+    #[test]
+    fn workers_are_limited_by_jobs_and_population() {
+
+        let mut manager = job_manager(3);
+
+        assert_eq!(manager.assign_workers("farm", 10, 4).unwrap(), 4);
+        assert_eq!(manager.assign_workers("mine", 3, 4).unwrap(), 0);
+        assert_eq!(manager.assign_workers("farm", 1, 4).unwrap(), 1);
+        assert_eq!(manager.assign_workers("mine", 5, 4).unwrap(), 3);
+        assert_eq!(manager.assign_workers("farm", 10, 100).unwrap(), 6);
+        assert!(matches!(manager.assign_workers("well", 1, 4), Err(BuildingWorkerError::NoJobs(_))));
+        assert_eq!(manager.total_workers(), 9);
+        assert_eq!(manager.total_job_slots(), 9);
+
+        // Buildings are released in name order once the population shrinks.
+        manager.limit_workers(4);
+        assert_eq!(manager.get("farm").unwrap().workers(), 1);
+        assert_eq!(manager.get("mine").unwrap().workers(), 3);
+
+        manager.set_active_count("mine", 1);
+        manager.limit_workers(4);
+        assert_eq!(manager.get("mine").unwrap().workers(), 1);
+
+    }
+
+    #[test]
+    fn staffing_scales_outputs_of_productions_with_jobs() {
+
+        let mut manager = job_manager(1);
+        manager.assign_workers("farm", 1, 10).unwrap();
+        manager.calculate(&ModifierStorage::new(), &ResourceManager::new());
+
+        assert_eq!(manager.get("farm").unwrap().staffing(), 0.5);
+        assert_eq!(to_f64(manager.get("farm").unwrap().calculated_outputs().value("grain")), 2f64);
+        assert_eq!(to_f64(manager.get("well").unwrap().calculated_outputs().value("grain")), 1f64);
+
+        manager.assign_workers("farm", 2, 10).unwrap();
+        manager.calculate(&ModifierStorage::new(), &ResourceManager::new());
+        assert_eq!(to_f64(manager.calculated_outputs().value("grain")), 5f64);
+
+    }
+
+}
@@ -1,7 +1,13 @@
 mod asset;
+mod construction;
 mod data;
 mod manager;
+mod save;
+mod snapshot;
 
-pub use asset::BuildingAsset;
-pub use data::Building;
-pub use manager::BuildingManager;
+pub use asset::{BuildingAsset, BuildingRequirement};
+pub use construction::BuildingConstruction;
+pub use data::{Building, BuildingInactiveReason, BuildingShutdown};
+pub use manager::{BuildingActivationError, BuildingConstructionError, BuildingConversionError, BuildingDemolishError, BuildingManager, BuildingProductionError, BuildingPurchaseError, BuildingWorkerError};
+pub use save::BuildingSave;
+pub use snapshot::{BuildingConstructionQueueSnapshot, BuildingConversionSnapshot, BuildingRequirementSnapshot, BuildingSnapshot};
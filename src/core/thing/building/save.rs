@@ -0,0 +1,64 @@
+use crate::core::thing::building::Building;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Saved state of a building.
+#[derive(Serialize, Deserialize)]
+pub struct BuildingSave {
+
+    /// Name of the building.
+    pub name: String,
+    /// Building count.
+    pub count: i32,
+    /// Active building count.
+    pub active_count: i32,
+    /// Population members assigned to the building's jobs.
+    #[serde(default)]
+    pub workers: i32,
+    /// Unlocked productions, sorted by name.
+    #[serde(default)]
+    pub unlocked_productions: Vec<String>,
+    /// Active productions, sorted by name.
+    #[serde(default)]
+    pub active_productions: Vec<String>,
+    /// Ticks left until switched productions start working.
+    #[serde(default)]
+    pub switching_productions: BTreeMap<String, u32>,
+    /// Is the building unlocked?
+    #[serde(default)]
+    pub is_unlocked: bool,
+
+}
+
+impl From<&Building> for BuildingSave {
+
+    fn from(building: &Building) -> Self {
+
+        let mut unlocked_productions = building.unlocked_productions()
+            .iter()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        unlocked_productions.sort();
+
+        let mut active_productions = building.active_productions()
+            .iter()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        active_productions.sort();
+
+        Self {
+            name: building.asset().name.clone(),
+            count: building.count(),
+            active_count: building.active_count(),
+            workers: building.workers(),
+            unlocked_productions,
+            active_productions,
+            switching_productions: building.switching_productions().iter().map(|(p, ticks)| (p.clone(), *ticks)).collect(),
+            is_unlocked: building.is_unlocked(),
+        }
+
+    }
+
+}
@@ -0,0 +1,270 @@
+use crate::core::number::Number;
+use crate::core::thing::building::{Building, BuildingConstruction, BuildingInactiveReason, BuildingManager, BuildingRequirement};
+use crate::core::thing::snapshot::ResourceValueSnapshot;
+use crate::core::number::to_number;
+use serde::Serialize;
+
+/// Snapshot of a building.
+#[derive(Serialize)]
+pub struct BuildingSnapshot {
+
+    /// Name of the building.
+    pub name: String,
+    /// Category of the building.
+    pub category: String,
+
+    /// Space used by a single building.
+    pub size: f64,
+    /// Building count.
+    pub count: i32,
+    /// Count of buildings in the construction queue.
+    pub queued_count: i32,
+    /// Maximum building count, `None` if the count is not limited.
+    pub max_count: Option<i32>,
+    /// Active building count, set by the player.
+    pub active_count: i32,
+    /// Count of buildings that are actually working.
+    pub working_count: i32,
+    /// Inactive buildings grouped by the reason.
+    pub inactive: Vec<BuildingInactiveSnapshot>,
+    /// Ratio of the output produced, limited by flow resources.
+    pub throttle: f64,
+    /// Population members assigned to the building's jobs.
+    pub workers: i32,
+    /// Job slots of the active buildings.
+    pub job_slots: i32,
+    /// Ratio of the job slots filled with workers, which scales outputs of productions with jobs.
+    pub staffing: f64,
+    /// Price of the next building.
+    pub prices: Vec<ResourceValueSnapshot>,
+    /// Productions of the building.
+    pub productions: Vec<BuildingProductionSnapshot>,
+
+    /// Is the building unlocked?
+    pub is_unlocked: bool,
+    /// Requirements of the locked building that are not met yet.
+    pub unmet_requirements: Vec<BuildingRequirementSnapshot>,
+    /// Is the building replaced by its unlocked next tier?
+    pub is_obsolete: bool,
+    /// Conversion of a single building into the next tier.
+    pub conversion: Option<BuildingConversionSnapshot>,
+
+}
+
+impl From<&Building> for BuildingSnapshot {
+
+    fn from(building: &Building) -> Self {
+
+        Self {
+            name: building.asset().name.clone(),
+            category: building.asset().category.clone(),
+            size: building.asset().size,
+            count: building.count(),
+            queued_count: building.queued_count(),
+            max_count: building.max_count(),
+            active_count: building.active_count(),
+            working_count: building.working_count(),
+            inactive: BuildingInactiveSnapshot::from_building(building),
+            throttle: building.throttle(),
+            workers: building.workers(),
+            job_slots: building.job_slots(),
+            staffing: building.staffing(),
+            prices: ResourceValueSnapshot::from_storage(building.calculated_prices()),
+            productions: BuildingProductionSnapshot::from_building(building),
+            is_unlocked: building.is_unlocked(),
+            unmet_requirements: Vec::new(),
+            is_obsolete: false,
+            conversion: None,
+        }
+
+    }
+
+}
+
+/// Snapshot of buildings that are inactive for the same reason.
+#[derive(Serialize)]
+pub struct BuildingInactiveSnapshot {
+
+    /// Reason why the buildings are inactive.
+    pub reason: BuildingInactiveReason,
+    /// Resource that caused an automatic shutdown.
+    pub resource: Option<String>,
+    /// Count of inactive buildings.
+    pub count: i32,
+
+}
+
+impl BuildingInactiveSnapshot {
+
+    /// Creates snapshots of the building's inactive counts, the player's choice first.
+    pub fn from_building(building: &Building) -> Vec<Self> {
+
+        let mut snapshots = Vec::new();
+
+        let player_count = building.count() - building.active_count();
+        if player_count > 0 {
+
+            snapshots.push(Self { reason: BuildingInactiveReason::Player, resource: None, count: player_count });
+
+        }
+
+        if let Some(shutdown) = building.shutdown().filter(|_| building.active_count() > 0) {
+
+            snapshots.push(Self { reason: shutdown.reason, resource: Some(shutdown.resource.clone()), count: building.active_count() });
+
+        }
+
+        snapshots
+
+    }
+
+}
+
+/// Snapshot of a building production.
+#[derive(Serialize)]
+pub struct BuildingProductionSnapshot {
+
+    /// Name of the production.
+    pub name: String,
+    /// Group of productions that cannot be active at the same time.
+    pub exclusive_group: Option<String>,
+    /// Price of switching into the production for every building.
+    pub switch_prices: Vec<ResourceValueSnapshot>,
+    /// Ticks left until the production starts working.
+    pub switch_ticks: u32,
+    /// Job slots of the production, per building.
+    pub jobs: u32,
+
+    /// Is the production unlocked?
+    pub is_unlocked: bool,
+    /// Is the production active?
+    pub is_active: bool,
+
+}
+
+impl BuildingProductionSnapshot {
+
+    /// Creates snapshots of the building's productions, in asset order.
+    pub fn from_building(building: &Building) -> Vec<Self> {
+
+        let count = building.count().max(0) as f64;
+
+        building.asset()
+            .productions
+            .iter()
+            .map(|production| Self {
+                name: production.name.clone(),
+                exclusive_group: production.exclusive_group.clone(),
+                switch_prices: production.switch_prices
+                    .iter()
+                    .map(|price| ResourceValueSnapshot { name: price.name.clone(), value: to_number(price.value * count) })
+                    .collect(),
+                switch_ticks: building.production_switch_ticks(&production.name),
+                jobs: production.jobs,
+                is_unlocked: building.is_production_unlocked(&production.name),
+                is_active: building.is_production_active(&production.name),
+            })
+            .collect()
+
+    }
+
+}
+
+/// Snapshot of a building requirement that is not met yet.
+#[derive(Serialize)]
+pub struct BuildingRequirementSnapshot {
+
+    /// The requirement.
+    #[serde(flatten)]
+    pub requirement: BuildingRequirement,
+    /// Current value compared with the requirement, if there is one.
+    pub current: Option<Number>,
+
+}
+
+/// Snapshot of converting a building into the next tier.
+#[derive(Serialize)]
+pub struct BuildingConversionSnapshot {
+
+    /// Name of the next tier building.
+    pub name: String,
+    /// Price of converting a single building.
+    pub prices: Vec<ResourceValueSnapshot>,
+
+}
+
+/// Snapshot of the construction queue.
+#[derive(Serialize)]
+pub struct BuildingConstructionQueueSnapshot {
+
+    /// Count of buildings constructed at the same time.
+    pub slots: u32,
+    /// Buildings waiting to be constructed, in the order they were purchased.
+    pub queue: Vec<BuildingConstructionSnapshot>,
+
+}
+
+impl From<&BuildingManager> for BuildingConstructionQueueSnapshot {
+
+    fn from(building_manager: &BuildingManager) -> Self {
+
+        let slots = building_manager.build_slots();
+
+        Self {
+            slots,
+            queue: building_manager.construction_queue()
+                .iter()
+                .enumerate()
+                .map(|(index, construction)| BuildingConstructionSnapshot::new(construction, index < slots as usize))
+                .collect(),
+        }
+
+    }
+
+}
+
+/// Snapshot of a building in the construction queue.
+#[derive(Serialize)]
+pub struct BuildingConstructionSnapshot {
+
+    /// Identifier of the construction, used for cancelling it.
+    pub id: u32,
+    /// Name of the building.
+    pub name: String,
+    /// Ticks of construction done.
+    pub progress: u32,
+    /// Ticks the construction takes.
+    pub total_ticks: u32,
+    /// Ratio of the construction done, between 0 and 1.
+    pub ratio: f64,
+    /// Is the construction in a build slot, or still waiting for one?
+    pub is_in_progress: bool,
+    /// Resources refunded if the construction is cancelled.
+    pub refund: Vec<ResourceValueSnapshot>,
+    /// Lower tier building converted into the building, given back if the construction is cancelled.
+    pub converted_from: Option<String>,
+
+}
+
+impl BuildingConstructionSnapshot {
+
+    /// Creates a snapshot of the construction.
+    fn new(construction: &BuildingConstruction, is_in_progress: bool) -> Self {
+
+        Self {
+            id: construction.id,
+            name: construction.name.clone(),
+            progress: construction.progress,
+            total_ticks: construction.total_ticks,
+            ratio: construction.ratio(),
+            is_in_progress,
+            refund: construction.prices
+                .iter()
+                .map(|(name, value)| ResourceValueSnapshot { name: name.clone(), value: *value })
+                .collect(),
+            converted_from: construction.converted_from.clone(),
+        }
+
+    }
+
+}
@@ -0,0 +1,91 @@
+use crate::core::number::Number;
+use crate::core::thing::snapshot::ResourceValueSnapshot;
+use serde::Serialize;
+
+/// Event that happened to things.
+#[derive(Serialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ThingEvent {
+
+    /// A resource was unlocked.
+    ResourceUnlocked {
+        /// Resource name.
+        name: String,
+    },
+    /// A building was unlocked.
+    BuildingUnlocked {
+        /// Building name.
+        name: String,
+    },
+    /// A gather action was unlocked.
+    GatherUnlocked {
+        /// Gather action name.
+        name: String,
+    },
+    /// A recipe was unlocked.
+    RecipeUnlocked {
+        /// Recipe name.
+        name: String,
+    },
+    /// A building was constructed.
+    BuildingConstructed {
+        /// Building name.
+        name: String,
+    },
+    /// An achievement was unlocked.
+    AchievementUnlocked {
+        /// Achievement name.
+        name: String,
+    },
+    /// Things were reset for prestige currency.
+    Prestiged {
+        /// Prestige currency name.
+        name: String,
+        /// Gained prestige currency.
+        gained: Number,
+    },
+    /// A gather action was triggered.
+    Gathered {
+        /// Gather action name.
+        name: String,
+        /// Yielded resources.
+        outputs: Vec<ResourceValueSnapshot>,
+    },
+
+}
+
+impl ThingEvent {
+
+    /// Returns type of the event, as it is serialized.
+    pub fn kind(&self) -> &'static str {
+
+        match self {
+            ThingEvent::ResourceUnlocked { .. } => "resource_unlocked",
+            ThingEvent::BuildingUnlocked { .. } => "building_unlocked",
+            ThingEvent::GatherUnlocked { .. } => "gather_unlocked",
+            ThingEvent::RecipeUnlocked { .. } => "recipe_unlocked",
+            ThingEvent::BuildingConstructed { .. } => "building_constructed",
+            ThingEvent::AchievementUnlocked { .. } => "achievement_unlocked",
+            ThingEvent::Prestiged { .. } => "prestiged",
+            ThingEvent::Gathered { .. } => "gathered",
+        }
+
+    }
+
+    /// Returns name of the thing the event happened to.
+    pub fn name(&self) -> &str {
+
+        match self {
+            ThingEvent::ResourceUnlocked { name } |
+            ThingEvent::BuildingUnlocked { name } |
+            ThingEvent::GatherUnlocked { name } |
+            ThingEvent::RecipeUnlocked { name } |
+            ThingEvent::BuildingConstructed { name } |
+            ThingEvent::AchievementUnlocked { name } |
+            ThingEvent::Prestiged { name, .. } |
+            ThingEvent::Gathered { name, .. } => name,
+        }
+
+    }
+
+}
@@ -0,0 +1,60 @@
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct GatherAsset {
+
+    /// Name of the gather action.
+    pub name: String,
+    /// Category of the gather action.
+    pub category: String,
+
+    /// Resources yielded by a single gather.
+    pub outputs: Vec<GatherOutput>,
+    /// Ticks to wait before the action can be triggered again.
+    #[serde(default)]
+    pub cooldown: u32,
+    /// Rules that unlock the gather action. The action unlocks when any of them is met.
+    #[serde(default = "default_unlock_rules")]
+    pub unlock_rules: Vec<GatherUnlockRule>,
+
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GatherUnlockRule {
+
+    /// Unlocked from the start of the game.
+    Start,
+    /// Unlocks when the resource is unlocked.
+    Resource {
+        /// Resource name.
+        name: String,
+    },
+    /// Unlocks when the building is owned.
+    Building {
+        /// Building name.
+        name: String,
+    },
+    /// Unlocks when the upgrade is bought.
+    Upgrade {
+        /// Upgrade name.
+        name: String,
+    },
+
+}
+
+fn default_unlock_rules() -> Vec<GatherUnlockRule> {
+
+    vec![GatherUnlockRule::Start]
+
+}
+
+#[derive(Deserialize)]
+pub struct GatherOutput {
+
+    /// Resource name.
+    pub name: String,
+    /// Resource value.
+    pub value: f64,
+
+}
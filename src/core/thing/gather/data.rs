@@ -0,0 +1,148 @@
+use super::GatherAsset;
+use crate::core::thing::modifier::{ModifierCalculationMethod, ModifierStorage};
+use crate::core::thing::resource::ResourceStorage;
+use crate::core::number::{to_number, Number};
+
+/// Gather action
+pub struct Gather {
+
+    /// Gather's asset.
+    asset: GatherAsset,
+
+    /// Ticks left until the action can be triggered again.
+    cooldown: u32,
+
+    /// Calculated outputs of a single gather.
+    calculated_outputs: ResourceStorage,
+
+    /// Is the gather action unlocked?
+    is_unlocked: bool,
+
+}
+
+impl From<GatherAsset> for Gather {
+
+    /// Creates a gather object from an asset.
+    fn from(asset: GatherAsset) -> Self {
+
+        Self {
+            asset,
+            cooldown: 0,
+            calculated_outputs: ResourceStorage::new(),
+            is_unlocked: false,
+        }
+
+    }
+
+}
+
+impl Gather {
+
+    /// Returns the gather's asset.
+    pub fn asset(&self) -> &GatherAsset {
+
+        &self.asset
+
+    }
+
+    /// Is the gather action unlocked?
+    pub fn is_unlocked(&self) -> bool {
+
+        self.is_unlocked
+
+    }
+
+    /// Unlocks the gather action.
+    pub fn unlock(&mut self) {
+
+        self.is_unlocked = true;
+
+    }
+
+}
+
+/// Implementations related to gather's cooldown.
+impl Gather {
+
+    /// Returns ticks left until the action can be triggered again.
+    pub fn cooldown(&self) -> u32 {
+
+        self.cooldown
+
+    }
+
+    /// Is the action ready to be triggered?
+    pub fn is_ready(&self) -> bool {
+
+        self.cooldown == 0
+
+    }
+
+    /// Starts the cooldown of the action.
+    pub fn start_cooldown(&mut self) {
+
+        self.cooldown = self.asset.cooldown;
+
+    }
+
+    /// Sets ticks left until the action can be triggered again, limited by the asset's cooldown.
+    pub fn set_cooldown(&mut self, cooldown: u32) {
+
+        self.cooldown = cooldown.min(self.asset.cooldown);
+
+    }
+
+    /// Advances the cooldown by a tick.
+    pub fn tick(&mut self) {
+
+        self.cooldown = self.cooldown.saturating_sub(1);
+
+    }
+
+}
+
+/// Implementations related to gather's calculation.
+impl Gather {
+
+    /// Calculates gather's outputs.
+    pub fn calculate(&mut self, modifier_storage: &ModifierStorage) {
+
+        self.calculated_outputs.clear();
+
+        for output in self.asset.outputs.iter() {
+
+            self.calculated_outputs.add(output.name.to_string(), self.create_modified_output_value(output.value, modifier_storage));
+
+        }
+
+    }
+
+    /// Calculated outputs of a single gather.
+    pub fn calculated_outputs(&self) -> &ResourceStorage {
+
+        &self.calculated_outputs
+
+    }
+
+    /// Creates output value using modifiers.
+    fn create_modified_output_value(&self, original_value: f64, modifier_storage: &ModifierStorage) -> Number {
+
+        let mut value = to_number(original_value);
+        value += 0f64 +
+            modifier_storage.value(&format!("gather.name.{}.output", self.asset.name), ModifierCalculationMethod::Base) +
+            modifier_storage.value(&format!("gather.category.{}.output", self.asset.category), ModifierCalculationMethod::Base) +
+            modifier_storage.value("gather.global.output", ModifierCalculationMethod::Base);
+        value *= 1f64 +
+            modifier_storage.value(&format!("gather.name.{}.output", self.asset.name), ModifierCalculationMethod::Additive) +
+            modifier_storage.value(&format!("gather.category.{}.output", self.asset.category), ModifierCalculationMethod::Additive) +
+            modifier_storage.value("gather.global.output", ModifierCalculationMethod::Additive);
+        value += 0f64 +
+            modifier_storage.value(&format!("gather.name.{}.output", self.asset.name), ModifierCalculationMethod::Flat) +
+            modifier_storage.value(&format!("gather.category.{}.output", self.asset.category), ModifierCalculationMethod::Flat) +
+            modifier_storage.value("gather.global.output", ModifierCalculationMethod::Flat);
+
+        value.max(0f64)
+
+    }
+
+}
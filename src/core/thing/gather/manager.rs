@@ -0,0 +1,297 @@
+use crate::core::thing::building::BuildingManager;
+use crate::core::thing::gather::{Gather, GatherAsset, GatherSave, GatherSnapshot, GatherUnlockRule};
+use crate::core::thing::modifier::ModifierStorage;
+use crate::core::thing::resource::ResourceManager;
+use crate::core::thing::upgrade::UpgradeManager;
+use crate::core::thing::snapshot::ResourceValueSnapshot;
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// Gather manager.
+pub struct GatherManager {
+
+    /// Gather actions.
+    gathers: HashMap<String, Gather>,
+
+}
+
+impl GatherManager {
+
+    /// Creates a new gather manager.
+    pub fn new() -> Self {
+
+        Self {
+            gathers: HashMap::new(),
+        }
+
+    }
+
+}
+
+/// Implementations related to loading and registering gather actions.
+impl GatherManager {
+
+    /// Loads gather action from string.
+    ///
+    /// # Params
+    ///
+    /// - `gather_asset_str`: JSON string of gather asset.
+    pub fn load_from_str(&mut self, gather_asset_str: &str) -> serde_json::Result<()> {
+
+        let result = serde_json::from_str(gather_asset_str)?;
+        self.load_from_asset(result);
+        Ok(())
+
+    }
+
+    /// Loads gather action from asset.
+    pub fn load_from_asset(&mut self, gather_asset: GatherAsset) {
+
+        let gather = Gather::from(gather_asset);
+        self.add(gather);
+
+    }
+
+    /// Adds a new gather action.
+    ///
+    /// The action is calculated without modifiers, so it can be triggered before the first tick.
+    /// Actions unlocked from the start are unlocked right away.
+    pub fn add(&mut self, mut gather: Gather) {
+
+        gather.calculate(&ModifierStorage::new());
+
+        if gather.asset().unlock_rules.iter().any(|rule| matches!(rule, GatherUnlockRule::Start)) {
+
+            gather.unlock();
+
+        }
+
+        self.gathers.insert(gather.asset().name.clone(), gather);
+
+    }
+
+}
+
+/// Implementations related to calculation.
+impl GatherManager {
+
+    /// Advances cooldowns and calculates all gather actions.
+    ///
+    /// # Params
+    ///
+    /// - `modifier_storage`: Modifiers that can be used for calculating gather actions.
+    pub fn calculate(&mut self, modifier_storage: &ModifierStorage) {
+
+        for (_, gather) in self.gathers.iter_mut() {
+
+            gather.tick();
+            gather.calculate(modifier_storage);
+
+        }
+
+    }
+
+}
+
+/// Implementations related to unlocking gather actions.
+impl GatherManager {
+
+    /// Unlocks gather actions whose unlock rules are met.
+    ///
+    /// Returns names of the newly unlocked gather actions.
+    pub fn update_unlocks(&mut self, resource_manager: &ResourceManager, building_manager: &BuildingManager, upgrade_manager: &UpgradeManager) -> Vec<String> {
+
+        let mut unlocked = Vec::new();
+
+        for (name, gather) in self.gathers.iter_mut() {
+
+            if gather.is_unlocked() { continue; }
+
+            let is_met = gather.asset()
+                .unlock_rules
+                .iter()
+                .any(|rule| match rule {
+                    GatherUnlockRule::Start => true,
+                    GatherUnlockRule::Resource { name } => resource_manager.is_unlocked(name),
+                    GatherUnlockRule::Building { name } => building_manager.get(name).is_some_and(|b| b.count() > 0),
+                    GatherUnlockRule::Upgrade { name } => upgrade_manager.count(name) > 0,
+                });
+
+            if is_met {
+
+                gather.unlock();
+                unlocked.push(name.clone());
+
+            }
+
+        }
+
+        unlocked.sort();
+        unlocked
+
+    }
+
+}
+
+#[derive(Error, Debug)]
+pub enum GatherError {
+    #[error("gather action '{0}' does not exist")]
+    NotFound(String),
+    #[error("gather action '{0}' is locked")]
+    Locked(String),
+    #[error("gather action '{0}' is cooling down for {1} ticks")]
+    CoolingDown(String, u32),
+}
+
+/// Implementations related to gathering.
+impl GatherManager {
+
+    /// Triggers the gather action and adds its outputs to the resources.
+    ///
+    /// Returns the yielded resources.
+    pub fn gather(&mut self, name: &str, resource_manager: &mut ResourceManager) -> Result<Vec<ResourceValueSnapshot>, GatherError> {
+
+        let gather = self.gathers
+            .get_mut(name)
+            .ok_or_else(|| GatherError::NotFound(name.to_string()))?;
+
+        if !gather.is_unlocked() { return Err(GatherError::Locked(name.to_string())); }
+
+        if !gather.is_ready() {
+
+            return Err(GatherError::CoolingDown(name.to_string(), gather.cooldown()));
+
+        }
+
+        for (resource, value) in gather.calculated_outputs().iter() {
+
+            resource_manager.add_count(resource, *value);
+
+        }
+
+        gather.start_cooldown();
+
+        Ok(ResourceValueSnapshot::from_storage(gather.calculated_outputs()))
+
+    }
+
+}
+
+/// Implementations related to snapshots.
+impl GatherManager {
+
+    /// Creates snapshots of gather actions, sorted by name.
+    pub fn snapshot(&self) -> Vec<GatherSnapshot> {
+
+        let mut snapshots = self.gathers
+            .values()
+            .map(GatherSnapshot::from)
+            .collect::<Vec<_>>();
+
+        snapshots.sort_by(|a, b| a.name.cmp(&b.name));
+        snapshots
+
+    }
+
+}
+
+/// Implementations related to saves.
+impl GatherManager {
+
+    /// Creates saves of gather actions, sorted by name.
+    pub fn save(&self) -> Vec<GatherSave> {
+
+        let mut saves = self.gathers
+            .values()
+            .map(GatherSave::from)
+            .collect::<Vec<_>>();
+
+        saves.sort_by(|a, b| a.name.cmp(&b.name));
+        saves
+
+    }
+
+    /// Restores gather actions from saves.
+    ///
+    /// Saves of gather actions that do not exist are ignored.
+    pub fn load_save(&mut self, saves: &[GatherSave]) {
+
+        for save in saves.iter() {
+
+            let Some(gather) = self.gathers.get_mut(&save.name) else { continue };
+
+            gather.set_cooldown(save.cooldown);
+            if save.is_unlocked { gather.unlock(); }
+
+        }
+
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{GatherError, GatherManager};
+    use crate::core::thing::modifier::ModifierStorage;
+    use crate::core::number::to_f64;
+    use crate::core::thing::building::BuildingManager;
+    use crate::core::thing::resource::ResourceManager;
+    use crate::core::thing::upgrade::UpgradeManager;
+
+    /// Creates a wood gather action with a cooldown of 3 ticks and the given unlock rules.
+    fn setup(unlock_rules: &str) -> (GatherManager, ResourceManager) {
+
+        let mut gather_manager = GatherManager::new();
+        gather_manager.load_from_str(&format!(r#"{{"name":"chop","category":"wood","outputs":[{{"name":"wood","value":2}}],"cooldown":3{unlock_rules}}}"#)).unwrap();
+
+        let mut resource_manager = ResourceManager::new();
+        resource_manager.load_from_str(r#"{"name":"wood","category":"raw","base_capacity":100,"modifiers":[]}"#).unwrap();
+
+        (gather_manager, resource_manager)
+
+    }
+
+    #[test]
+    fn gather_yields_outputs_before_the_first_tick() {
+
+        let (mut gather_manager, mut resource_manager) = setup("");
+
+        assert_eq!(gather_manager.gather("chop", &mut resource_manager).unwrap().len(), 1);
+        assert_eq!(to_f64(resource_manager.count("wood")), 2f64);
+        assert!(matches!(gather_manager.gather("chop", &mut resource_manager), Err(GatherError::CoolingDown(_, 3))));
+
+    }
+
+    #[test]
+    fn locked_gather_is_rejected_until_its_rule_is_met() {
+
+        let (mut gather_manager, mut resource_manager) = setup(r#","unlock_rules":[{"type":"upgrade","name":"axe"}]"#);
+
+        assert!(matches!(gather_manager.gather("chop", &mut resource_manager), Err(GatherError::Locked(_))));
+        assert!(gather_manager.update_unlocks(&resource_manager, &BuildingManager::new(), &UpgradeManager::new()).is_empty());
+
+        let mut upgrade_manager = UpgradeManager::new();
+        upgrade_manager.load_from_str(r#"{"name":"axe","category":"tools","price":[],"price_multiplier":1,"unlocks":[],"modifiers":[]}"#).unwrap();
+        upgrade_manager.purchase("axe", &mut resource_manager).unwrap();
+
+        assert_eq!(gather_manager.update_unlocks(&resource_manager, &BuildingManager::new(), &upgrade_manager), vec!["chop".to_string()]);
+        assert!(gather_manager.gather("chop", &mut resource_manager).is_ok());
+
+    }
+
+    #[test]
+    fn save_restores_cooldown_and_unlock() {
+
+        let (mut gather_manager, mut resource_manager) = setup("");
+        gather_manager.gather("chop", &mut resource_manager).unwrap();
+        gather_manager.calculate(&ModifierStorage::new());
+
+        let (mut loaded, mut resource_manager) = setup(r#","unlock_rules":[{"type":"resource","name":"wood"}]"#);
+        loaded.load_save(&gather_manager.save());
+
+        assert!(matches!(loaded.gather("chop", &mut resource_manager), Err(GatherError::CoolingDown(_, 2))));
+
+    }
+
+}
@@ -0,0 +1,11 @@
+mod asset;
+mod data;
+mod manager;
+mod save;
+mod snapshot;
+
+pub use asset::{GatherAsset, GatherUnlockRule};
+pub use data::Gather;
+pub use manager::{GatherError, GatherManager};
+pub use save::GatherSave;
+pub use snapshot::GatherSnapshot;
@@ -0,0 +1,31 @@
+use crate::core::thing::gather::Gather;
+use serde::{Deserialize, Serialize};
+
+/// Saved state of a gather action.
+#[derive(Serialize, Deserialize)]
+pub struct GatherSave {
+
+    /// Name of the gather action.
+    pub name: String,
+    /// Ticks left until the action can be triggered again.
+    #[serde(default)]
+    pub cooldown: u32,
+    /// Is the gather action unlocked?
+    #[serde(default)]
+    pub is_unlocked: bool,
+
+}
+
+impl From<&Gather> for GatherSave {
+
+    fn from(gather: &Gather) -> Self {
+
+        Self {
+            name: gather.asset().name.clone(),
+            cooldown: gather.cooldown(),
+            is_unlocked: gather.is_unlocked(),
+        }
+
+    }
+
+}
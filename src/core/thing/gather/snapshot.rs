@@ -0,0 +1,38 @@
+use crate::core::thing::gather::Gather;
+use crate::core::thing::snapshot::ResourceValueSnapshot;
+use serde::Serialize;
+
+/// Snapshot of a gather action.
+#[derive(Serialize)]
+pub struct GatherSnapshot {
+
+    /// Name of the gather action.
+    pub name: String,
+    /// Category of the gather action.
+    pub category: String,
+
+    /// Outputs of a single gather.
+    pub outputs: Vec<ResourceValueSnapshot>,
+    /// Ticks left until the action can be triggered again.
+    pub cooldown: u32,
+
+    /// Is the gather action unlocked?
+    pub is_unlocked: bool,
+
+}
+
+impl From<&Gather> for GatherSnapshot {
+
+    fn from(gather: &Gather) -> Self {
+
+        Self {
+            name: gather.asset().name.clone(),
+            category: gather.asset().category.clone(),
+            outputs: ResourceValueSnapshot::from_storage(gather.calculated_outputs()),
+            cooldown: gather.cooldown(),
+            is_unlocked: gather.is_unlocked(),
+        }
+
+    }
+
+}
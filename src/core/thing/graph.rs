@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+
+/// Groups nodes into strongly connected components.
+///
+/// Components are returned in dependency order, so every node comes after the nodes it depends on.
+/// A component with more than one node is a cycle.
+///
+/// # Params
+///
+/// - `nodes`: Names of the nodes. The order is used to keep the result deterministic.
+/// - `dependencies`: Nodes that each node depends on.
+pub fn strongly_connected_components(nodes: &[String], dependencies: &HashMap<String, Vec<String>>) -> Vec<Vec<String>> {
+
+    let mut state = TarjanState {
+        index: 0,
+        indices: HashMap::new(),
+        low_links: HashMap::new(),
+        stack: Vec::new(),
+        components: Vec::new(),
+    };
+
+    for node in nodes.iter() {
+
+        if !state.indices.contains_key(node) {
+
+            state.visit(node, dependencies);
+
+        }
+
+    }
+
+    state.components
+
+}
+
+/// State of the Tarjan's algorithm.
+struct TarjanState {
+    index: usize,
+    indices: HashMap<String, usize>,
+    low_links: HashMap<String, usize>,
+    stack: Vec<String>,
+    components: Vec<Vec<String>>,
+}
+
+impl TarjanState {
+
+    fn visit(&mut self, node: &str, dependencies: &HashMap<String, Vec<String>>) {
+
+        self.indices.insert(node.to_string(), self.index);
+        self.low_links.insert(node.to_string(), self.index);
+        self.index += 1;
+        self.stack.push(node.to_string());
+
+        for dependency in dependencies.get(node).into_iter().flatten() {
+
+            if !self.indices.contains_key(dependency) {
+
+                self.visit(dependency, dependencies);
+                let low_link = self.low_links[node].min(self.low_links[dependency]);
+                self.low_links.insert(node.to_string(), low_link);
+
+            } else if self.stack.contains(dependency) {
+
+                let low_link = self.low_links[node].min(self.indices[dependency]);
+                self.low_links.insert(node.to_string(), low_link);
+
+            }
+
+        }
+
+        if self.low_links[node] == self.indices[node] {
+
+            let mut component = Vec::new();
+
+            while let Some(member) = self.stack.pop() {
+
+                let is_root = member == node;
+                component.push(member);
+                if is_root { break; }
+
+            }
+
+            component.reverse();
+            self.components.push(component);
+
+        }
+
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::strongly_connected_components;
+    use std::collections::HashMap;
+
+    fn graph(edges: &[(&str, &[&str])]) -> (Vec<String>, HashMap<String, Vec<String>>) {
+
+        let nodes = edges.iter().map(|(node, _)| node.to_string()).collect();
+        let dependencies = edges
+            .iter()
+            .map(|(node, deps)| (node.to_string(), deps.iter().map(|d| d.to_string()).collect()))
+            .collect();
+
+        (nodes, dependencies)
+
+    }
+
+    #[test]
+    fn chain_is_ordered_by_dependencies() {
+
+        let (nodes, dependencies) = graph(&[("a", &["b"]), ("b", &["c"]), ("c", &[])]);
+
+        assert_eq!(strongly_connected_components(&nodes, &dependencies), vec![vec!["c"], vec!["b"], vec!["a"]]);
+
+    }
+
+    #[test]
+    fn cycle_is_grouped_after_its_dependencies() {
+
+        let (nodes, dependencies) = graph(&[("a", &["b"]), ("b", &["a", "c"]), ("c", &[]), ("d", &["a"])]);
+        let components = strongly_connected_components(&nodes, &dependencies);
+
+        assert_eq!(components.len(), 3);
+        assert_eq!(components[0], vec!["c"]);
+        assert_eq!(components[1].len(), 2);
+        assert!(components[1].contains(&"a".to_string()) && components[1].contains(&"b".to_string()));
+        assert_eq!(components[2], vec!["d"]);
+
+    }
+
+    #[test]
+    fn self_loop_is_a_single_component() {
+
+        let (nodes, dependencies) = graph(&[("a", &["a"]), ("b", &["a"])]);
+
+        assert_eq!(strongly_connected_components(&nodes, &dependencies), vec![vec!["a"], vec!["b"]]);
+
+    }
+
+    #[test]
+    fn unconnected_nodes_keep_their_order() {
+
+        let (nodes, dependencies) = graph(&[("b", &[]), ("a", &[]), ("c", &[])]);
+
+        assert_eq!(strongly_connected_components(&nodes, &dependencies), vec![vec!["b"], vec!["a"], vec!["c"]]);
+
+    }
+
+}
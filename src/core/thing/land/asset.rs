@@ -0,0 +1,9 @@
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct LandAsset {
+
+    /// Land capacity before modifiers.
+    pub base_capacity: f64,
+
+}
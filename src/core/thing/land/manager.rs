@@ -0,0 +1,269 @@
+use crate::core::number::{to_number, Number};
+use crate::core::thing::building::BuildingManager;
+use crate::core::thing::land::{LandAsset, LandSnapshot};
+use crate::core::thing::modifier::{ModifierCalculationMethod, ModifierStorage};
+
+/// Land manager.
+///
+/// Every building uses `size × count` of the land.
+/// The land is not limited until a land asset is loaded.
+pub struct LandManager {
+
+    /// Land's asset.
+    asset: Option<LandAsset>,
+
+    /// Calculated land capacity.
+    capacity: Number,
+    /// Space used by buildings.
+    used: Number,
+
+}
+
+impl LandManager {
+
+    /// Creates a new land manager.
+    pub fn new() -> Self {
+
+        Self {
+            asset: None,
+            capacity: to_number(0f64),
+            used: to_number(0f64),
+        }
+
+    }
+
+    /// Is the land limited?
+    pub fn is_limited(&self) -> bool {
+
+        self.asset.is_some()
+
+    }
+
+    /// Returns land capacity, `None` if the land is not limited.
+    pub fn capacity(&self) -> Option<Number> {
+
+        self.asset.as_ref().map(|_| self.capacity)
+
+    }
+
+    /// Returns space used by buildings.
+    pub fn used(&self) -> Number {
+
+        self.used
+
+    }
+
+    /// Returns space left for buildings, `None` if the land is not limited.
+    pub fn free(&self) -> Option<Number> {
+
+        self.capacity().map(|capacity| (capacity - self.used).max(0f64))
+
+    }
+
+}
+
+/// Implementations related to loading land.
+impl LandManager {
+
+    /// Loads land from string.
+    ///
+    /// # Params
+    ///
+    /// - `land_asset_str`: JSON string of land asset.
+    pub fn load_from_str(&mut self, land_asset_str: &str) -> serde_json::Result<()> {
+
+        let result = serde_json::from_str(land_asset_str)?;
+        self.load_from_asset(result);
+        Ok(())
+
+    }
+
+    /// Loads land from asset, replacing the previous one.
+    pub fn load_from_asset(&mut self, land_asset: LandAsset) {
+
+        self.capacity = to_number(land_asset.base_capacity);
+        self.asset = Some(land_asset);
+
+    }
+
+}
+
+/// Implementations related to calculation.
+impl LandManager {
+
+    /// Calculates land capacity and used space.
+    ///
+    /// # Params
+    ///
+    /// - `modifier_storage`: Modifiers that can change the capacity, `land.capacity`.
+    /// - `building_manager`: Buildings that use the land.
+    pub fn calculate(&mut self, modifier_storage: &ModifierStorage, building_manager: &BuildingManager) {
+
+        if let Some(asset) = &self.asset {
+
+            let mut capacity = to_number(asset.base_capacity);
+            capacity += modifier_storage.value("land.capacity", ModifierCalculationMethod::Base);
+            capacity *= 1f64 + modifier_storage.value("land.capacity", ModifierCalculationMethod::Additive);
+            capacity += modifier_storage.value("land.capacity", ModifierCalculationMethod::Flat);
+
+            self.capacity = capacity.max(0f64);
+
+        }
+
+        self.calculate_used(building_manager);
+
+    }
+
+    /// Calculates space used by buildings.
+    ///
+    /// Buildings in the construction queue already use their space.
+    pub fn calculate_used(&mut self, building_manager: &BuildingManager) {
+
+        self.used = building_manager
+            .iter()
+            .map(|(_, building)| to_number(building.asset().size.max(0f64) * (building.count() + building.queued_count()) as f64))
+            .sum();
+
+    }
+
+}
+
+/// Implementations related to snapshots.
+impl LandManager {
+
+    /// Creates a snapshot of the land.
+    pub fn snapshot(&self) -> LandSnapshot {
+
+        LandSnapshot::from(self)
+
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::LandManager;
+    use crate::core::number::{to_f64, to_number};
+    use crate::core::thing::building::{BuildingManager, BuildingPurchaseError};
+    use crate::core::thing::modifier::{ModifierCalculationMethod, ModifierEntry, ModifierStorage};
+    use crate::core::thing::resource::ResourceManager;
+
+    /// Creates land with the capacity.
+    fn land(base_capacity: f64) -> LandManager {
+
+        let mut land_manager = LandManager::new();
+        land_manager.load_from_str(&format!(r#"{{"base_capacity":{base_capacity}}}"#)).unwrap();
+
+        land_manager
+
+    }
+
+    /// Creates a hut of size 2 built instantly and a mill of size 3 built in 5 ticks, both costing 1 wood.
+    fn buildings(resource_manager: &ResourceManager) -> BuildingManager {
+
+        let mut building_manager = BuildingManager::new();
+        building_manager.load_from_str(r#"{"name":"hut","category":"housing","prices":[{"name":"wood","value":1}],"price_multiplier":1,"size":2,"productions":[]}"#).unwrap();
+        building_manager.load_from_str(r#"{"name":"mill","category":"industry","prices":[{"name":"wood","value":1}],"price_multiplier":1,"size":3,"build_ticks":5,"productions":[]}"#).unwrap();
+        building_manager.unlock("hut");
+        building_manager.unlock("mill");
+        building_manager.calculate(&ModifierStorage::new(), resource_manager);
+
+        building_manager
+
+    }
+
+    fn resources() -> ResourceManager {
+
+        let mut resource_manager = ResourceManager::new();
+        resource_manager.load_from_str(r#"{"name":"wood","category":"raw","base_capacity":100,"modifiers":[]}"#).unwrap();
+        resource_manager.add_count("wood", to_number(100f64));
+
+        resource_manager
+
+    }
+
+    fn purchase(building_manager: &mut BuildingManager, land_manager: &mut LandManager, resource_manager: &mut ResourceManager, name: &str) -> Result<(), BuildingPurchaseError> {
+
+        building_manager.purchase(name, land_manager.free(), resource_manager, &ModifierStorage::new())?;
+        land_manager.calculate_used(building_manager);
+
+        Ok(())
+
+    }
+
+    #[test]
+    fn land_is_not_limited_without_an_asset() {
+
+        let mut resource_manager = resources();
+        let mut building_manager = buildings(&resource_manager);
+        let mut land_manager = LandManager::new();
+
+        purchase(&mut building_manager, &mut land_manager, &mut resource_manager, "hut").unwrap();
+        land_manager.calculate(&ModifierStorage::new(), &building_manager);
+
+        assert!(!land_manager.is_limited());
+        assert_eq!(land_manager.capacity(), None);
+        assert_eq!(land_manager.free(), None);
+        assert_eq!(to_f64(land_manager.used()), 2f64);
+
+    }
+
+    #[test]
+    fn capacity_is_modified_by_land_capacity_modifiers() {
+
+        let mut modifier_storage = ModifierStorage::new();
+        modifier_storage.add(ModifierEntry::new("land.capacity".to_string(), to_number(5f64), ModifierCalculationMethod::Base));
+        modifier_storage.add(ModifierEntry::new("land.capacity".to_string(), to_number(0.5), ModifierCalculationMethod::Additive));
+        modifier_storage.add(ModifierEntry::new("land.capacity".to_string(), to_number(2f64), ModifierCalculationMethod::Flat));
+
+        let mut land_manager = land(10f64);
+        land_manager.calculate(&modifier_storage, &BuildingManager::new());
+
+        assert_eq!(land_manager.capacity().map(to_f64), Some(24.5));
+        assert_eq!(land_manager.free().map(to_f64), Some(24.5));
+
+    }
+
+    #[test]
+    fn used_space_includes_queued_buildings() {
+
+        let mut resource_manager = resources();
+        let mut building_manager = buildings(&resource_manager);
+        let mut land_manager = land(20f64);
+
+        purchase(&mut building_manager, &mut land_manager, &mut resource_manager, "hut").unwrap();
+        purchase(&mut building_manager, &mut land_manager, &mut resource_manager, "hut").unwrap();
+        purchase(&mut building_manager, &mut land_manager, &mut resource_manager, "mill").unwrap();
+
+        assert_eq!(building_manager.get("mill").unwrap().count(), 0);
+        assert_eq!(to_f64(land_manager.used()), 7f64);
+        assert_eq!(land_manager.free().map(to_f64), Some(13f64));
+
+    }
+
+    #[test]
+    fn purchase_needs_free_space_and_demolish_frees_it() {
+
+        let mut resource_manager = resources();
+        let mut building_manager = buildings(&resource_manager);
+        let mut land_manager = land(5f64);
+
+        purchase(&mut building_manager, &mut land_manager, &mut resource_manager, "hut").unwrap();
+        purchase(&mut building_manager, &mut land_manager, &mut resource_manager, "hut").unwrap();
+
+        let error = purchase(&mut building_manager, &mut land_manager, &mut resource_manager, "hut").unwrap_err();
+        assert!(matches!(error, BuildingPurchaseError::NotEnoughSpace { needed, free } if to_f64(needed) == 2f64 && to_f64(free) == 1f64));
+        assert_eq!(building_manager.get("hut").unwrap().count(), 2);
+        assert!((to_f64(resource_manager.count("wood")) - 98f64).abs() < 1e-9);
+
+        building_manager.demolish("hut", &mut resource_manager, &ModifierStorage::new()).unwrap();
+        land_manager.calculate_used(&building_manager);
+        assert_eq!(land_manager.free().map(to_f64), Some(3f64));
+
+        purchase(&mut building_manager, &mut land_manager, &mut resource_manager, "hut").unwrap();
+        assert_eq!(building_manager.get("hut").unwrap().count(), 2);
+
+    }
+
+}
@@ -0,0 +1,7 @@
+mod asset;
+mod manager;
+mod snapshot;
+
+pub use asset::LandAsset;
+pub use manager::LandManager;
+pub use snapshot::LandSnapshot;
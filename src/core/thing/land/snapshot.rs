@@ -0,0 +1,30 @@
+use crate::core::number::Number;
+use crate::core::thing::land::LandManager;
+use serde::Serialize;
+
+/// Snapshot of the land.
+#[derive(Serialize)]
+pub struct LandSnapshot {
+
+    /// Land capacity, `None` if the land is not limited.
+    pub capacity: Option<Number>,
+    /// Space used by buildings.
+    pub used: Number,
+    /// Space left for buildings, `None` if the land is not limited.
+    pub free: Option<Number>,
+
+}
+
+impl From<&LandManager> for LandSnapshot {
+
+    fn from(land_manager: &LandManager) -> Self {
+
+        Self {
+            capacity: land_manager.capacity(),
+            used: land_manager.used(),
+            free: land_manager.free(),
+        }
+
+    }
+
+}
@@ -1,7 +1,19 @@
-use crate::core::thing::building::BuildingManager;
+use crate::core::thing::achievement::AchievementManager;
+use crate::core::thing::affordability::AffordabilityReport;
+use crate::core::thing::building::{BuildingActivationError, BuildingConstructionError, BuildingConversionError, BuildingDemolishError, BuildingManager, BuildingProductionError, BuildingPurchaseError, BuildingWorkerError};
+use crate::core::thing::event::ThingEvent;
+use crate::core::thing::gather::{GatherError, GatherManager};
+use crate::core::thing::land::LandManager;
+use crate::core::number::{to_number, Number};
 use crate::core::thing::modifier::ModifierStorage;
-use crate::core::thing::resource::ResourceManager;
-use crate::core::thing::upgrade::UpgradeManager;
+use crate::core::thing::recipe::{RecipeCraftError, RecipeManager};
+use crate::core::thing::population::PopulationManager;
+use crate::core::thing::prestige::{PrestigeError, PrestigeManager, PrestigeUpgradePurchaseError};
+use crate::core::thing::resource::{RateWindow, ResourceManager, ResourceStorage};
+use crate::core::thing::save::ThingSave;
+use crate::core::thing::snapshot::ThingSnapshot;
+use crate::core::thing::statistics::LifetimeStatistics;
+use crate::core::thing::upgrade::{UpgradeManager, UpgradePurchaseError, UpgradeTreeError, UpgradeTreeSnapshot};
 use thiserror::Error;
 
 pub struct ThingManager {
@@ -9,10 +21,19 @@ pub struct ThingManager {
     building_manager: BuildingManager,
     resource_manager: ResourceManager,
     upgrade_manager: UpgradeManager,
+    recipe_manager: RecipeManager,
+    gather_manager: GatherManager,
+    land_manager: LandManager,
+    population_manager: PopulationManager,
+    achievement_manager: AchievementManager,
+    prestige_manager: PrestigeManager,
     
-    prev_tick_modifiers: ModifierStorage,
+    /// Loaded assets as load type and JSON string, replayed to rebuild things on a prestige reset.
+    assets: Vec<(String, String)>,
+    statistics: LifetimeStatistics,
+    calculated_modifiers: ModifierStorage,
 
-    is_first_tick: bool,
+    events: Vec<ThingEvent>,
 
 }
 
@@ -34,39 +55,448 @@ impl ThingManager {
             building_manager: BuildingManager::new(),
             resource_manager: ResourceManager::new(),
             upgrade_manager: UpgradeManager::new(),
-            prev_tick_modifiers: ModifierStorage::new(),
-            is_first_tick: true,
+            recipe_manager: RecipeManager::new(),
+            gather_manager: GatherManager::new(),
+            land_manager: LandManager::new(),
+            population_manager: PopulationManager::new(),
+            achievement_manager: AchievementManager::new(),
+            prestige_manager: PrestigeManager::new(),
+            assets: Vec::new(),
+            statistics: LifetimeStatistics::new(),
+            calculated_modifiers: ModifierStorage::new(),
+            events: Vec::new(),
         }
 
     }
     
+    /// Advances things by a tick.
+    ///
+    /// Modifiers from upgrades, achievements, prestige upgrades and resources are calculated first, then buildings are calculated in dependency order.
+    /// Every modifier is applied within the tick it was generated in.
+    /// Achievements are checked last, against the state at the end of the tick.
     pub fn tick(&mut self) {
         
         let mut current_tick_modifiers = ModifierStorage::new();
 
-        if !self.is_first_tick {
-
-            self.resource_manager.set_production(self.building_manager.calculated_outputs());
-            self.resource_manager.set_consumption(self.building_manager.calculated_upkeeps());
-
-        }
-
-        self.resource_manager.calculate();
         self.upgrade_manager.calculate();
-        self.building_manager.calculate(&self.prev_tick_modifiers, &self.resource_manager);
+        self.resource_manager.calculate();
 
         current_tick_modifiers.combine(self.upgrade_manager.calculated_modifiers());
+        current_tick_modifiers.combine(self.achievement_manager.calculated_modifiers());
+        current_tick_modifiers.combine(self.prestige_manager.calculated_modifiers());
         current_tick_modifiers.combine(self.resource_manager.calculated_modifiers());
+
+        self.building_manager.calculate(&current_tick_modifiers, &self.resource_manager);
+
         current_tick_modifiers.combine(self.building_manager.calculated_modifiers());
+
+        for name in self.building_manager.advance_construction(&current_tick_modifiers) {
+
+            self.push_event(ThingEvent::BuildingConstructed { name });
+
+        }
+
+        self.recipe_manager.calculate(&current_tick_modifiers);
+        self.gather_manager.calculate(&current_tick_modifiers);
+        self.land_manager.calculate(&current_tick_modifiers, &self.building_manager);
+        self.population_manager.calculate(&current_tick_modifiers, &self.resource_manager);
+
+        let mut upkeeps = ResourceStorage::new();
+        upkeeps.combine(self.building_manager.calculated_upkeeps());
+        upkeeps.combine(self.population_manager.calculated_upkeeps());
+
+        self.resource_manager.set_capacity(self.building_manager.calculated_storages());
+        self.resource_manager.set_production(self.building_manager.calculated_outputs());
+        self.resource_manager.set_consumption(&upkeeps);
+        self.resource_manager.produce(&current_tick_modifiers);
+
+        self.population_manager.grow(&mut self.resource_manager);
+        self.building_manager.limit_workers(self.population_manager.count(&self.resource_manager));
+        self.resource_manager.record_statistics();
+
+        self.statistics.add("ticks".to_string(), to_number(1f64));
+        for (name, value) in self.building_manager.calculated_outputs().iter() {
+
+            self.statistics.add(format!("resource.{name}.produced"), *value);
+
+        }
+        
+        self.calculated_modifiers = current_tick_modifiers;
+        
+        for name in self.building_manager.update_unlocks(&self.resource_manager, &self.upgrade_manager) {
+            
+            self.push_event(ThingEvent::BuildingUnlocked { name });
+            
+        }
+        
+        self.update_resource_unlocks();
+        self.update_recipe_unlocks();
+        self.update_gather_unlocks();
+        
+        for name in self.achievement_manager.update_unlocks(&self.resource_manager, &self.building_manager, &self.upgrade_manager, &self.statistics) {
+            
+            self.push_event(ThingEvent::AchievementUnlocked { name });
+            
+        }
         
-        if self.is_first_tick {
+    }
+    
+    /// Unlocks resources whose unlock rules are met.
+    fn update_resource_unlocks(&mut self) {
+        
+        for name in self.resource_manager.update_unlocks(&self.building_manager, &self.upgrade_manager) {
+            
+            self.push_event(ThingEvent::ResourceUnlocked { name });
+            
+        }
+        
+    }
+    
+    /// Unlocks recipes whose unlock rules are met.
+    fn update_recipe_unlocks(&mut self) {
+        
+        for name in self.recipe_manager.update_unlocks(&self.resource_manager, &self.upgrade_manager) {
+            
+            self.push_event(ThingEvent::RecipeUnlocked { name });
+            
+        }
+        
+    }
+    
+    /// Unlocks gather actions whose unlock rules are met.
+    fn update_gather_unlocks(&mut self) {
+        
+        for name in self.gather_manager.update_unlocks(&self.resource_manager, &self.building_manager, &self.upgrade_manager) {
+            
+            self.push_event(ThingEvent::GatherUnlocked { name });
+            
+        }
+        
+    }
+    
+    /// Records the event in the lifetime statistics and queues it for the UI.
+    fn push_event(&mut self, event: ThingEvent) {
+        
+        self.statistics.record_event(&event);
+        self.events.push(event);
+        
+    }
+    
+    /// Takes events that happened since the last call.
+    pub fn drain_events(&mut self) -> Vec<ThingEvent> {
+        
+        std::mem::take(&mut self.events)
+        
+    }
+    
+    /// Creates a snapshot of things for the UI.
+    pub fn snapshot(&self) -> ThingSnapshot {
+        
+        ThingSnapshot {
+            resources: self.resource_manager.snapshot(),
+            buildings: self.building_manager.snapshot(&self.resource_manager, &self.upgrade_manager, &self.calculated_modifiers),
+            construction: self.building_manager.construction_snapshot(),
+            upgrades: self.upgrade_manager.snapshot(),
+            recipes: self.recipe_manager.snapshot(),
+            gathers: self.gather_manager.snapshot(),
+            land: self.land_manager.snapshot(),
+            population: self.population_manager.snapshot(&self.resource_manager, &self.building_manager),
+            achievements: self.achievement_manager.snapshot(),
+            statistics: self.statistics.save(),
+            prestige: self.prestige_manager.snapshot(&self.statistics),
+        }
+        
+    }
+    
+    /// Creates a save of the player's progress.
+    pub fn save(&self) -> ThingSave {
+        
+        ThingSave {
+            resources: self.resource_manager.save(),
+            buildings: self.building_manager.save(),
+            construction: self.building_manager.save_construction(),
+            upgrades: self.upgrade_manager.save(),
+            gathers: self.gather_manager.save(),
+            achievements: self.achievement_manager.save(),
+            statistics: self.statistics.save(),
+            prestige: self.prestige_manager.save(),
+        }
+        
+    }
+    
+    /// Restores the player's progress from a save.
+    ///
+    /// Things must be loaded first. Calculated values are refreshed by the next tick.
+    pub fn load_save(&mut self, save: &ThingSave) {
+        
+        self.resource_manager.load_save(&save.resources);
+        self.building_manager.load_save(&save.buildings);
+        self.building_manager.load_construction_save(&save.construction, &self.calculated_modifiers);
+        self.upgrade_manager.load_save(&save.upgrades);
+        self.gather_manager.load_save(&save.gathers);
+        self.achievement_manager.load_save(&save.achievements);
+        self.statistics.load_save(&save.statistics);
+        self.prestige_manager.load_save(&save.prestige);
+        self.calculated_modifiers.combine(self.prestige_manager.calculated_modifiers());
+        self.land_manager.calculate_used(&self.building_manager);
+        
+    }
+    
+    /// Returns modifiers calculated in the last tick.
+    pub fn calculated_modifiers(&self) -> &ModifierStorage {
+        
+        &self.calculated_modifiers
+        
+    }
+    
+    /// Returns average net change of the resource per tick in the window.
+    pub fn resource_average_rate(&self, name: &str, window: RateWindow) -> f64 {
+        
+        self.resource_manager.average_rate(name, window)
+        
+    }
+    
+    /// Returns estimated ticks until the resource reaches its capacity.
+    pub fn resource_ticks_until_capacity(&self, name: &str, window: RateWindow) -> Option<f64> {
+        
+        self.resource_manager.ticks_until_capacity(name, window)
+        
+    }
+    
+    /// Returns estimated ticks until the resource runs empty.
+    pub fn resource_ticks_until_empty(&self, name: &str, window: RateWindow) -> Option<f64> {
+        
+        self.resource_manager.ticks_until_empty(name, window)
+        
+    }
+    
+    /// Returns affordability of the building's next purchase.
+    pub fn building_affordability(&self, name: &str) -> Option<AffordabilityReport> {
+        
+        self.building_manager
+            .get(name)
+            .map(|b| AffordabilityReport::new(b.calculated_prices(), &self.resource_manager))
+        
+    }
+    
+    /// Returns affordability of the upgrade's next purchase.
+    pub fn upgrade_affordability(&self, name: &str) -> Option<AffordabilityReport> {
+        
+        self.upgrade_manager
+            .get(name)
+            .map(|u| AffordabilityReport::new(u.calculated_prices(), &self.resource_manager))
+        
+    }
+    
+    /// Purchases the next level of an upgrade, and returns the new level.
+    ///
+    /// Resources, recipes and gather actions unlocked by the upgrade are unlocked right away.
+    pub fn purchase_upgrade(&mut self, name: &str) -> Result<i32, UpgradePurchaseError> {
+        
+        let level = self.upgrade_manager.purchase(name, &mut self.resource_manager)?;
+        self.statistics.add(format!("upgrade.{name}.purchased"), to_number(1f64));
+        self.update_resource_unlocks();
+        self.update_recipe_unlocks();
+        self.update_gather_unlocks();
+        
+        Ok(level)
+        
+    }
+    
+    /// Purchases a building if there are enough resources and space.
+    pub fn purchase_building(&mut self, name: &str) -> Result<(), BuildingPurchaseError> {
+        
+        self.building_manager.purchase(name, self.land_manager.free(), &mut self.resource_manager, &self.calculated_modifiers)?;
+        self.land_manager.calculate_used(&self.building_manager);
+        self.statistics.add(format!("building.{name}.purchased"), to_number(1f64));
+        
+        Ok(())
+        
+    }
+    
+    /// Sets how many of the buildings are active, and returns the count after clamping to the building count.
+    pub fn set_building_active_count(&mut self, name: &str, active_count: i32) -> Result<i32, BuildingActivationError> {
+        
+        self.building_manager.activate(name, active_count)
+        
+    }
+    
+    /// Assigns population members to the building's jobs, and returns the workers after clamping.
+    pub fn assign_building_workers(&mut self, name: &str, workers: i32) -> Result<i32, BuildingWorkerError> {
+        
+        self.building_manager.assign_workers(name, workers, self.population_manager.count(&self.resource_manager))
+        
+    }
+    
+    /// Activates or deactivates a production of the building, paying the switch price.
+    pub fn set_building_production(&mut self, name: &str, production: &str, active: bool) -> Result<(), BuildingProductionError> {
+        
+        self.building_manager.set_active_production(name, production, active, &mut self.resource_manager)
+        
+    }
+    
+    /// Converts buildings into the next tier.
+    pub fn convert_building(&mut self, name: &str, count: i32) -> Result<(), BuildingConversionError> {
+        
+        self.building_manager.convert(name, count, self.land_manager.free(), &mut self.resource_manager, &self.calculated_modifiers)?;
+        self.land_manager.calculate_used(&self.building_manager);
+        
+        Ok(())
+        
+    }
+    
+    /// Cancels a construction, refunding the price paid for the building.
+    pub fn cancel_construction(&mut self, id: u32) -> Result<(), BuildingConstructionError> {
+        
+        self.building_manager.cancel_construction(id, &mut self.resource_manager, &self.calculated_modifiers)?;
+        self.land_manager.calculate_used(&self.building_manager);
+        
+        Ok(())
+        
+    }
+    
+    /// Demolishes a building, refunding part of its price, and frees its space.
+    pub fn demolish_building(&mut self, name: &str) -> Result<(), BuildingDemolishError> {
+        
+        self.building_manager.demolish(name, &mut self.resource_manager, &self.calculated_modifiers)?;
+        self.land_manager.calculate_used(&self.building_manager);
+        
+        Ok(())
+        
+    }
+    
+    /// Crafts the recipe.
+    pub fn craft(&mut self, name: &str, count: u32) -> Result<u32, RecipeCraftError> {
+        
+        let count = self.recipe_manager.craft(name, count, &mut self.resource_manager)?;
+        self.statistics.add(format!("recipe.{name}.crafted"), to_number(count as f64));
+        
+        Ok(count)
+        
+    }
+    
+    /// Crafts the recipe as many times as inputs and output capacity allow.
+    pub fn craft_max(&mut self, name: &str) -> Result<u32, RecipeCraftError> {
+        
+        let count = self.recipe_manager.craft_max(name, &mut self.resource_manager)?;
+        self.statistics.add(format!("recipe.{name}.crafted"), to_number(count as f64));
+        
+        Ok(count)
+        
+    }
+    
+    /// Triggers the gather action.
+    pub fn gather(&mut self, name: &str) -> Result<(), GatherError> {
+        
+        let outputs = self.gather_manager.gather(name, &mut self.resource_manager)?;
+        for output in outputs.iter() {
+            
+            self.statistics.add(format!("resource.{}.produced", output.name), output.value);
+            
+        }
+        
+        self.push_event(ThingEvent::Gathered { name: name.to_string(), outputs });
+        
+        Ok(())
+        
+    }
+    
+    /// Returns groups of buildings that generate modifiers for each other or for themselves.
+    ///
+    /// Each group is resolved by repeating the calculation, so the content should avoid them.
+    pub fn modifier_cycles(&self) -> &[Vec<String>] {
+        
+        self.building_manager.calculation_cycles()
+        
+    }
+    
+    /// Returns groups of buildings whose modifiers did not settle in the last tick.
+    pub fn unresolved_modifier_cycles(&self) -> &[Vec<String>] {
+        
+        self.building_manager.unresolved_cycles()
+        
+    }
+    
+    /// Validates prerequisites of loaded upgrades, removing upgrades that do not form a tree.
+    ///
+    /// Must be called once all things are loaded.
+    pub fn validate_upgrade_tree(&mut self) -> Vec<UpgradeTreeError> {
+        
+        self.upgrade_manager.validate_tree()
+        
+    }
+    
+    /// Creates layout of the research tree for the UI.
+    pub fn upgrade_tree(&self) -> UpgradeTreeSnapshot {
+        
+        self.upgrade_manager.tree()
+        
+    }
+    
+}
 
-            self.building_manager.calculate(&current_tick_modifiers, &self.resource_manager);
+/// Implementations related to prestige.
+impl ThingManager {
+    
+    /// Returns prestige currency a reset would give right now.
+    pub fn prestige_preview(&self) -> Number {
+        
+        self.prestige_manager.preview(&self.statistics)
+        
+    }
+    
+    /// Resets things to their loaded assets for prestige currency, and returns the gained currency.
+    ///
+    /// The prestige, lifetime statistics and unlocked achievements are kept.
+    /// Modifiers of prestige upgrades apply from the first tick after the reset.
+    pub fn prestige(&mut self) -> Result<Number, PrestigeError> {
+        
+        let gained = self.prestige_manager.reset(&self.statistics)?;
+        
+        let mut next = ThingManager::new();
+        
+        for (load_type, string) in self.assets.iter() {
+            
+            if Self::is_prestige_load_type(load_type) { continue; }
+            
+            // Assets were parsed when they were loaded, so they can not fail now.
+            let _ = next.load_thing_from_str(load_type, string);
             
         }
         
-        self.prev_tick_modifiers = current_tick_modifiers;
-        self.is_first_tick = false;
+        let _ = next.validate_upgrade_tree();
+        next.achievement_manager.load_save(&self.achievement_manager.save());
+        
+        next.assets = std::mem::take(&mut self.assets);
+        next.statistics = std::mem::replace(&mut self.statistics, LifetimeStatistics::new());
+        next.prestige_manager = std::mem::replace(&mut self.prestige_manager, PrestigeManager::new());
+        next.events = std::mem::take(&mut self.events);
+        
+        next.calculated_modifiers.combine(next.achievement_manager.calculated_modifiers());
+        next.calculated_modifiers.combine(next.prestige_manager.calculated_modifiers());
+        
+        let name = next.prestige_manager.name().unwrap_or_default().to_string();
+        next.push_event(ThingEvent::Prestiged { name, gained });
+        
+        *self = next;
+        
+        Ok(gained)
+        
+    }
+    
+    /// Purchases the next level of a prestige upgrade, and returns the new level.
+    ///
+    /// Its modifiers apply from the next tick.
+    pub fn purchase_prestige_upgrade(&mut self, name: &str) -> Result<i32, PrestigeUpgradePurchaseError> {
+        
+        self.prestige_manager.purchase(name)
+        
+    }
+    
+    /// Is the load type kept by the prestige manager through resets?
+    fn is_prestige_load_type(load_type: &str) -> bool {
+        
+        matches!(load_type, "prestige" | "prestige_upgrade")
         
     }
     
@@ -82,14 +512,181 @@ pub enum ThingManagerLoadError {
 
 impl ThingManager {
     
+    /// Loads a thing from string, and keeps the string to rebuild things on a prestige reset.
     pub fn load_from_str(&mut self, load_type: &str, string: &str) -> Result<(), ThingManagerLoadError> {
         
+        self.load_thing_from_str(load_type, string)?;
+        self.assets.push((load_type.to_string(), string.to_string()));
+        
+        Ok(())
+        
+    }
+    
+    fn load_thing_from_str(&mut self, load_type: &str, string: &str) -> Result<(), ThingManagerLoadError> {
+        
         match load_type {
             "building" => Ok(self.building_manager.load_from_str(string)?),
             "resource" => Ok(self.resource_manager.load_from_str(string)?),
+            "upgrade" => Ok(self.upgrade_manager.load_from_str(string)?),
+            "recipe" => Ok(self.recipe_manager.load_from_str(string)?),
+            "gather" => Ok(self.gather_manager.load_from_str(string)?),
+            "land" => Ok(self.land_manager.load_from_str(string)?),
+            "population" => Ok(self.population_manager.load_from_str(string)?),
+            "achievement" => Ok(self.achievement_manager.load_from_str(string)?),
+            "prestige" => Ok(self.prestige_manager.load_from_str(string)?),
+            "prestige_upgrade" => Ok(self.prestige_manager.load_upgrade_from_str(string)?),
             _ => Err(ThingManagerLoadError::WrongLoadType(load_type.to_string()))
         }
         
     }
     
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::ThingManager;
+    use crate::core::number::{to_f64, to_number};
+    use crate::core::thing::event::ThingEvent;
+    use crate::core::thing::modifier::ModifierCalculationMethod;
+    use crate::core::thing::resource::RateWindow;
+
+    #[test]
+    fn upgrade_purchase_unlocks_resources() {
+
+        let mut thing_manager = ThingManager::new();
+        thing_manager.load_from_str("resource", r#"{"name":"iron","category":"raw","base_capacity":10,"modifiers":[],"unlock_rules":[{"type":"upgrade","name":"mining"}]}"#).unwrap();
+        thing_manager.load_from_str("upgrade", r#"{"name":"mining","category":"tools","price":[],"price_multiplier":1,"unlocks":[],"modifiers":[]}"#).unwrap();
+
+        assert!(thing_manager.snapshot().resources.is_empty());
+
+        thing_manager.purchase_upgrade("mining").unwrap();
+
+        assert_eq!(thing_manager.snapshot().resources.len(), 1);
+        assert!(matches!(thing_manager.drain_events().as_slice(), [ThingEvent::ResourceUnlocked { name }] if name == "iron"));
+
+    }
+
+    #[test]
+    fn rate_history_includes_overflow_conversion() {
+
+        let mut thing_manager = ThingManager::new();
+        thing_manager.load_from_str("resource", r#"{"name":"wood","category":"raw","base_capacity":10,"modifiers":[],"overflow":{"name":"sawdust","ratio":0.5}}"#).unwrap();
+        thing_manager.load_from_str("resource", r#"{"name":"sawdust","category":"raw","base_capacity":10,"modifiers":[]}"#).unwrap();
+        thing_manager.resource_manager.add_count("wood", to_number(14f64));
+
+        thing_manager.tick();
+
+        assert!((thing_manager.resource_average_rate("sawdust", RateWindow::Short) - 2f64).abs() < 1e-9);
+
+    }
+
+    #[test]
+    fn rate_history_includes_population_growth() {
+
+        let mut thing_manager = ThingManager::new();
+        thing_manager.load_from_str("resource", r#"{"name":"people","category":"population","base_capacity":10,"modifiers":[]}"#).unwrap();
+        thing_manager.load_from_str("population", r#"{"resource":"people","growth":1,"food":[]}"#).unwrap();
+
+        thing_manager.tick();
+        thing_manager.tick();
+
+        assert_eq!(thing_manager.resource_average_rate("people", RateWindow::Short), 1f64);
+        assert_eq!(thing_manager.resource_ticks_until_capacity("people", RateWindow::Short), Some(8f64));
+
+    }
+
+    /// Creates a thing manager with a resource, gather, buildings, an upgrade, an achievement and a prestige.
+    fn loaded() -> ThingManager {
+
+        let mut thing_manager = ThingManager::new();
+        thing_manager.load_from_str("resource", r#"{"name":"wood","category":"raw","base_capacity":1000,"modifiers":[]}"#).unwrap();
+        thing_manager.load_from_str("gather", r#"{"name":"chop","category":"forest","outputs":[{"name":"wood","value":100}],"cooldown":0}"#).unwrap();
+        thing_manager.load_from_str("building", r#"{"name":"hut","category":"housing","prices":[{"name":"wood","value":10}],"price_multiplier":1,"size":1,"productions":[
+            {"name":"default","upkeeps":[],"outputs":[],"modifiers":[],"storages":[]},
+            {"name":"kiln","upkeeps":[],"outputs":[],"modifiers":[],"storages":[]}]}"#).unwrap();
+        thing_manager.load_from_str("building", r#"{"name":"mill","category":"industry","prices":[{"name":"wood","value":10}],"price_multiplier":1,"size":1,"build_ticks":5,"productions":[
+            {"name":"default","upkeeps":[],"outputs":[],"modifiers":[],"storages":[]}]}"#).unwrap();
+        thing_manager.load_from_str("upgrade", r#"{"name":"axe","category":"tools","price":[{"name":"wood","value":10}],"price_multiplier":1,"unlocks":[],"modifiers":[]}"#).unwrap();
+        thing_manager.load_from_str("achievement", r#"{"name":"settler","category":"housing","conditions":[{"type":"building_count","name":"hut","count":1}]}"#).unwrap();
+        thing_manager.load_from_str("prestige", r#"{"name":"stars","formula":[{"statistic":"resource.wood.produced","exponent":1}]}"#).unwrap();
+        thing_manager.load_from_str("prestige_upgrade", r#"{"name":"sight","category":"stars","price":50,"price_multiplier":1,"modifiers":[]}"#).unwrap();
+        thing_manager.tick();
+
+        thing_manager
+
+    }
+
+    #[test]
+    fn save_round_trip_restores_progress() {
+
+        let mut thing_manager = loaded();
+        thing_manager.gather("chop").unwrap();
+        thing_manager.prestige().unwrap();
+        thing_manager.purchase_prestige_upgrade("sight").unwrap();
+
+        thing_manager.tick();
+        thing_manager.gather("chop").unwrap();
+        thing_manager.purchase_building("hut").unwrap();
+        thing_manager.purchase_building("hut").unwrap();
+        thing_manager.set_building_active_count("hut", 1).unwrap();
+        thing_manager.building_manager.unlock_production("hut", "kiln");
+        thing_manager.set_building_production("hut", "kiln", true).unwrap();
+        thing_manager.purchase_building("mill").unwrap();
+        thing_manager.purchase_upgrade("axe").unwrap();
+        thing_manager.tick();
+
+        let save = serde_json::to_string(&thing_manager.save()).unwrap();
+        let mut restored = loaded();
+        restored.load_save(&serde_json::from_str(&save).unwrap());
+
+        assert_eq!(serde_json::to_string(&restored.save()).unwrap(), save);
+
+        assert_eq!(to_f64(restored.resource_manager.count("wood")), 60f64);
+
+        let hut = restored.building_manager.get("hut").unwrap();
+        assert_eq!((hut.count(), hut.active_count()), (2, 1));
+        assert!(hut.is_production_active("default"));
+        assert!(hut.is_production_active("kiln"));
+
+        let queue = restored.building_manager.construction_queue();
+        assert_eq!(queue.len(), 1);
+        assert_eq!((queue[0].name.as_str(), queue[0].progress), ("mill", 1));
+
+        assert_eq!(restored.upgrade_manager.count("axe"), 1);
+        assert!(restored.snapshot().achievements.iter().any(|a| a.name == "settler" && a.is_unlocked));
+        assert_eq!(to_f64(restored.statistics.value("resource.wood.produced")), 200f64);
+        assert_eq!(to_f64(restored.statistics.value("building.hut.purchased")), 2f64);
+
+        let prestige = restored.prestige_manager.save();
+        assert_eq!((to_f64(prestige.currency), to_f64(prestige.earned), prestige.resets), (50f64, 100f64, 1));
+        assert_eq!((prestige.upgrades[0].name.as_str(), prestige.upgrades[0].level), ("sight", 1));
+
+    }
+
+    #[test]
+    fn achievement_unlocks_once_and_survives_prestige() {
+
+        let mut thing_manager = loaded();
+        thing_manager.load_from_str("achievement", r#"{"name":"gatherer","category":"forest","conditions":[{"type":"event","event":"gathered","name":"chop"}],"modifiers":[{"name":"global.speed","value":0.1,"calculation":"additive"}]}"#).unwrap();
+
+        thing_manager.gather("chop").unwrap();
+        thing_manager.tick();
+        thing_manager.tick();
+
+        let unlocks = thing_manager.drain_events()
+            .into_iter()
+            .filter(|event| matches!(event, ThingEvent::AchievementUnlocked { name } if name == "gatherer"))
+            .count();
+        assert_eq!(unlocks, 1);
+
+        thing_manager.prestige().unwrap();
+        thing_manager.tick();
+
+        assert!(thing_manager.snapshot().achievements.iter().any(|a| a.name == "gatherer" && a.is_unlocked));
+        assert!(thing_manager.drain_events().iter().all(|event| !matches!(event, ThingEvent::AchievementUnlocked { .. })));
+        assert_eq!(thing_manager.calculated_modifiers().value("global.speed", ModifierCalculationMethod::Additive), 0.1);
+
+    }
+
+}
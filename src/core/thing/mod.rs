@@ -2,7 +2,33 @@ mod manager;
 mod building;
 mod resource;
 mod upgrade;
+mod recipe;
+mod gather;
+mod land;
+mod population;
+mod prestige;
 mod modifier;
+mod graph;
+mod affordability;
+mod achievement;
+mod statistics;
+mod event;
+mod save;
+mod snapshot;
 
+/// Number of ticks in a second.
+pub const TICKS_PER_SECOND: usize = 2;
+
+pub use affordability::{AffordabilityEntry, AffordabilityReport};
+pub use building::{BuildingActivationError, BuildingConstructionError, BuildingConversionError, BuildingDemolishError, BuildingProductionError, BuildingPurchaseError, BuildingWorkerError};
+pub use event::ThingEvent;
 pub use manager::ThingManager;
 pub use manager::ThingManagerLoadError;
+pub use gather::GatherError;
+pub use modifier::ModifierScaling;
+pub use prestige::{PrestigeError, PrestigeUpgradePurchaseError};
+pub use recipe::RecipeCraftError;
+pub use resource::RateWindow;
+pub use save::ThingSave;
+pub use snapshot::ThingSnapshot;
+pub use upgrade::UpgradePurchaseError;
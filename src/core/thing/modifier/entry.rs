@@ -1,13 +1,15 @@
-#[derive(Clone)]
+use crate::core::Number;
+
+#[derive(Clone, PartialEq)]
 pub struct ModifierEntry {
     name: String,
-    value: f64,
+    value: Number,
     calculation: ModifierCalculationMethod,
 }
 
 impl ModifierEntry {
 
-    pub fn new(name: String, value: f64, calculation: ModifierCalculationMethod) -> Self {
+    pub fn new(name: String, value: Number, calculation: ModifierCalculationMethod) -> Self {
 
         Self {
             name,
@@ -29,19 +31,19 @@ impl ModifierEntry {
         
     }
 
-    pub fn value(&self) -> f64 {
+    pub fn value(&self) -> Number {
 
         self.value
 
     }
 
-    pub fn set_value(&mut self, value: f64) {
+    pub fn set_value(&mut self, value: Number) {
 
         self.value = value;
 
     }
 
-    pub fn add_value(&mut self, value: f64) {
+    pub fn add_value(&mut self, value: Number) {
 
         self.value += value;
 
@@ -55,7 +57,7 @@ impl ModifierEntry {
 
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq, Eq)]
 pub enum ModifierCalculationMethod {
     Base,
     Flat,
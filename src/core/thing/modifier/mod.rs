@@ -1,6 +1,8 @@
 mod storage;
 mod entry;
+mod scaling;
 
 pub use entry::ModifierCalculationMethod;
 pub use entry::ModifierEntry;
+pub use scaling::ModifierScaling;
 pub use storage::ModifierStorage;
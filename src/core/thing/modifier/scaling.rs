@@ -0,0 +1,81 @@
+use serde::Deserialize;
+
+/// Curve that scales a modifier value by a count.
+#[derive(Deserialize, Copy, Clone, Default, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ModifierScaling {
+    /// `count`
+    #[default]
+    Linear,
+    /// `ln(1 + count)`
+    Logarithmic,
+    /// `sqrt(count)`
+    SquareRoot,
+    /// Linear until `threshold`, square root of the remaining count after that.
+    SoftCap {
+        threshold: f64,
+    },
+    /// Increases once every `every` counts.
+    Step {
+        every: f64,
+    },
+}
+
+impl ModifierScaling {
+
+    /// Returns a multiplier of the modifier value for the count.
+    ///
+    /// Negative counts are treated as 0.
+    pub fn evaluate(&self, count: f64) -> f64 {
+
+        let count = count.max(0f64);
+
+        match *self {
+            ModifierScaling::Linear => count,
+            ModifierScaling::Logarithmic => count.ln_1p(),
+            ModifierScaling::SquareRoot => count.sqrt(),
+            ModifierScaling::SoftCap { threshold } => {
+
+                let threshold = threshold.max(0f64);
+                if count <= threshold { count } else { threshold + (count - threshold).sqrt() }
+
+            },
+            ModifierScaling::Step { every } => {
+
+                if every > 0f64 { (count / every).floor() } else { 0f64 }
+
+            },
+        }
+
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::ModifierScaling;
+
+    #[test]
+    fn curves_scale_by_count() {
+
+        assert_eq!(ModifierScaling::Linear.evaluate(4f64), 4f64);
+        assert_eq!(ModifierScaling::Logarithmic.evaluate(std::f64::consts::E - 1f64), 1f64);
+        assert_eq!(ModifierScaling::SquareRoot.evaluate(9f64), 3f64);
+        assert_eq!(ModifierScaling::SoftCap { threshold: 5f64 }.evaluate(3f64), 3f64);
+        assert_eq!(ModifierScaling::SoftCap { threshold: 5f64 }.evaluate(14f64), 8f64);
+        assert_eq!(ModifierScaling::Step { every: 3f64 }.evaluate(8f64), 2f64);
+
+    }
+
+    #[test]
+    fn negative_counts_and_invalid_parameters_do_not_scale() {
+
+        assert_eq!(ModifierScaling::Linear.evaluate(-2f64), 0f64);
+        assert_eq!(ModifierScaling::SquareRoot.evaluate(-4f64), 0f64);
+        assert_eq!(ModifierScaling::SoftCap { threshold: -1f64 }.evaluate(4f64), 2f64);
+        assert_eq!(ModifierScaling::Step { every: 0f64 }.evaluate(10f64), 0f64);
+
+    }
+
+}
@@ -2,8 +2,10 @@ use std::collections::hash_map::Iter;
 use std::collections::HashMap;
 
 use super::{ModifierCalculationMethod, ModifierEntry};
+use crate::core::number::{to_number, Number};
 
 /// Modifier storage
+#[derive(Clone, PartialEq)]
 pub struct ModifierStorage {
     modifiers: HashMap<String, ModifierEntry>,
 }
@@ -44,12 +46,12 @@ impl ModifierStorage {
         
     }
     
-    pub fn value(&self, name: &str, method: ModifierCalculationMethod) -> f64 {
+    pub fn value(&self, name: &str, method: ModifierCalculationMethod) -> Number {
         
         self.modifiers
             .get(&format!("{}.{}", name, method.key()))
             .map(|v| v.value())
-            .unwrap_or(0f64)
+            .unwrap_or(to_number(0f64))
         
     }
 
@@ -0,0 +1,35 @@
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct PopulationAsset {
+
+    /// Name of the resource that counts the population.
+    ///
+    /// Its capacity is the housing, supplied by building storages.
+    pub resource: String,
+    /// Members gained per tick while there is free housing, before modifiers.
+    pub growth: f64,
+    /// Food eaten by a single member per tick, before modifiers.
+    #[serde(default)]
+    pub food: Vec<PopulationFood>,
+    /// Ratio of the population lost per tick while food has run out, at least a single member.
+    #[serde(default = "default_starvation")]
+    pub starvation: f64,
+
+}
+
+fn default_starvation() -> f64 {
+
+    0.05f64
+
+}
+
+#[derive(Deserialize)]
+pub struct PopulationFood {
+
+    /// Resource name.
+    pub name: String,
+    /// Resource value.
+    pub value: f64,
+
+}
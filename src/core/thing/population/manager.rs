@@ -0,0 +1,302 @@
+use crate::core::number::{to_f64, to_number, Number};
+use crate::core::thing::building::BuildingManager;
+use crate::core::thing::modifier::{ModifierCalculationMethod, ModifierStorage};
+use crate::core::thing::population::{PopulationAsset, PopulationSnapshot};
+use crate::core::thing::resource::{ResourceManager, ResourceStorage};
+
+/// Population manager.
+///
+/// The population is a resource that grows toward its capacity and eats food.
+/// There is no population until a population asset is loaded.
+pub struct PopulationManager {
+
+    /// Population's asset.
+    asset: Option<PopulationAsset>,
+
+    /// Calculated members gained per tick while there is free housing.
+    calculated_growth: Number,
+    /// Calculated food eaten by the population per tick.
+    calculated_upkeeps: ResourceStorage,
+    /// Food that has run out, if the population is starving.
+    starving: Option<String>,
+    /// Members gained or lost in the last tick.
+    change: Number,
+
+}
+
+impl PopulationManager {
+
+    /// Creates a new population manager.
+    pub fn new() -> Self {
+
+        Self {
+            asset: None,
+            calculated_growth: to_number(0f64),
+            calculated_upkeeps: ResourceStorage::new(),
+            starving: None,
+            change: to_number(0f64),
+        }
+
+    }
+
+    /// Returns name of the resource that counts the population, `None` if there is no population.
+    pub fn resource(&self) -> Option<&str> {
+
+        self.asset.as_ref().map(|asset| asset.resource.as_str())
+
+    }
+
+    /// Returns count of whole population members.
+    pub fn count(&self, resource_manager: &ResourceManager) -> i32 {
+
+        match self.resource() {
+            Some(resource) => to_f64(resource_manager.count(resource).floor()).clamp(0f64, i32::MAX as f64) as i32,
+            None => 0,
+        }
+
+    }
+
+    /// Returns food that has run out, if the population is starving.
+    pub fn starving(&self) -> Option<&str> {
+
+        self.starving.as_deref()
+
+    }
+
+    /// Returns members gained or lost in the last tick.
+    pub fn change(&self) -> Number {
+
+        self.change
+
+    }
+
+    /// Calculated members gained per tick while there is free housing.
+    pub fn calculated_growth(&self) -> Number {
+
+        self.calculated_growth
+
+    }
+
+    /// Calculated food eaten by the population per tick.
+    pub fn calculated_upkeeps(&self) -> &ResourceStorage {
+
+        &self.calculated_upkeeps
+
+    }
+
+}
+
+/// Implementations related to loading population.
+impl PopulationManager {
+
+    /// Loads population from string.
+    ///
+    /// # Params
+    ///
+    /// - `population_asset_str`: JSON string of population asset.
+    pub fn load_from_str(&mut self, population_asset_str: &str) -> serde_json::Result<()> {
+
+        let result = serde_json::from_str(population_asset_str)?;
+        self.load_from_asset(result);
+        Ok(())
+
+    }
+
+    /// Loads population from asset, replacing the previous one.
+    pub fn load_from_asset(&mut self, population_asset: PopulationAsset) {
+
+        self.asset = Some(population_asset);
+
+    }
+
+}
+
+/// Implementations related to calculation.
+impl PopulationManager {
+
+    /// Calculates growth and food of the population.
+    ///
+    /// # Params
+    ///
+    /// - `modifier_storage`: Modifiers that can change the growth, `population.growth`, and the food of a member, `population.food`.
+    /// - `resource_manager`: Resource manager for the population count.
+    pub fn calculate(&mut self, modifier_storage: &ModifierStorage, resource_manager: &ResourceManager) {
+
+        self.calculated_upkeeps.clear();
+
+        let Some(asset) = &self.asset else { return };
+
+        let mut growth = to_number(asset.growth);
+        growth += modifier_storage.value("population.growth", ModifierCalculationMethod::Base);
+        growth *= 1f64 + modifier_storage.value("population.growth", ModifierCalculationMethod::Additive);
+        growth += modifier_storage.value("population.growth", ModifierCalculationMethod::Flat);
+
+        self.calculated_growth = growth.max(0f64);
+
+        let members = self.count(resource_manager) as f64;
+
+        for food in asset.food.iter() {
+
+            let mut value = to_number(food.value);
+            value += modifier_storage.value("population.food", ModifierCalculationMethod::Base);
+            value *= 1f64 + modifier_storage.value("population.food", ModifierCalculationMethod::Additive);
+            value += modifier_storage.value("population.food", ModifierCalculationMethod::Flat);
+
+            self.calculated_upkeeps.add(food.name.clone(), value.max(0f64) * members);
+
+        }
+
+    }
+
+    /// Grows the population toward its capacity, or starves it while food has run out.
+    ///
+    /// Must be called after resources are produced, so the food eaten in the tick is taken into account.
+    pub fn grow(&mut self, resource_manager: &mut ResourceManager) {
+
+        self.change = to_number(0f64);
+        self.starving = None;
+
+        let Some(asset) = &self.asset else { return };
+
+        let mut foods = asset.food
+            .iter()
+            .map(|food| food.name.as_str())
+            .collect::<Vec<_>>();
+
+        foods.sort();
+
+        self.starving = foods
+            .into_iter()
+            .find(|food| self.calculated_upkeeps.value(food) > 0f64 && resource_manager.is_drained(food))
+            .map(|food| food.to_string());
+
+        let count = resource_manager.count(&asset.resource);
+
+        self.change = if self.starving.is_some() {
+
+            -(count * asset.starvation.clamp(0f64, 1f64)).max(1f64).min(count)
+
+        } else {
+
+            (resource_manager.capacity(&asset.resource) - count).max(0f64).min(self.calculated_growth)
+
+        };
+
+        resource_manager.add_count(&asset.resource, self.change);
+
+    }
+
+}
+
+/// Implementations related to snapshots.
+impl PopulationManager {
+
+    /// Creates a snapshot of the population, `None` if there is no population.
+    pub fn snapshot(&self, resource_manager: &ResourceManager, building_manager: &BuildingManager) -> Option<PopulationSnapshot> {
+
+        self.resource().map(|_| PopulationSnapshot::new(self, resource_manager, building_manager))
+
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::PopulationManager;
+    use crate::core::number::{to_f64, to_number};
+    use crate::core::thing::modifier::{ModifierCalculationMethod, ModifierEntry, ModifierStorage};
+    use crate::core::thing::resource::ResourceManager;
+
+    /// Creates people housed up to 10 that grow by 1 and each eat 0.5 grain, and 100 grain.
+    fn managers(people: f64) -> (PopulationManager, ResourceManager) {
+
+        let mut resource_manager = ResourceManager::new();
+        resource_manager.load_from_str(r#"{"name":"people","category":"population","base_capacity":10,"modifiers":[]}"#).unwrap();
+        resource_manager.load_from_str(r#"{"name":"grain","category":"food","base_capacity":100,"modifiers":[]}"#).unwrap();
+        resource_manager.add_count("people", to_number(people));
+        resource_manager.add_count("grain", to_number(100f64));
+
+        let mut population_manager = PopulationManager::new();
+        population_manager.load_from_str(r#"{"resource":"people","growth":1,"food":[{"name":"grain","value":0.5}],"starvation":0.2}"#).unwrap();
+        population_manager.calculate(&ModifierStorage::new(), &resource_manager);
+
+        (population_manager, resource_manager)
+
+    }
+
+    /// Drains grain while the people eat more than is produced.
+    fn drain_grain(population_manager: &mut PopulationManager, resource_manager: &mut ResourceManager) {
+
+        resource_manager.add_count("grain", -resource_manager.count("grain"));
+        resource_manager.set_consumption(population_manager.calculated_upkeeps());
+        population_manager.calculate(&ModifierStorage::new(), resource_manager);
+
+    }
+
+    fn assert_close(value: f64, expected: f64) {
+
+        assert!((value - expected).abs() < 1e-9, "{value} != {expected}");
+
+    }
+
+    #[test]
+    fn every_member_eats_food() {
+
+        let (mut population_manager, resource_manager) = managers(4.5);
+        assert_eq!(population_manager.count(&resource_manager), 4);
+        assert_close(to_f64(population_manager.calculated_upkeeps().value("grain")), 2f64);
+
+        let mut modifier_storage = ModifierStorage::new();
+        modifier_storage.add(ModifierEntry::new("population.food".to_string(), to_number(0.5), ModifierCalculationMethod::Base));
+        population_manager.calculate(&modifier_storage, &resource_manager);
+        assert_close(to_f64(population_manager.calculated_upkeeps().value("grain")), 4f64);
+
+    }
+
+    #[test]
+    fn population_grows_until_capacity() {
+
+        let (mut population_manager, mut resource_manager) = managers(8.5);
+
+        population_manager.grow(&mut resource_manager);
+        assert_close(to_f64(population_manager.change()), 1f64);
+
+        population_manager.grow(&mut resource_manager);
+        assert_close(to_f64(population_manager.change()), 0.5);
+        assert_close(to_f64(resource_manager.count("people")), 10f64);
+
+        population_manager.grow(&mut resource_manager);
+        assert_close(to_f64(population_manager.change()), 0f64);
+        assert_close(to_f64(resource_manager.count("people")), 10f64);
+        assert_eq!(population_manager.starving(), None);
+
+    }
+
+    #[test]
+    fn population_starves_while_food_is_drained() {
+
+        let (mut population_manager, mut resource_manager) = managers(10f64);
+        drain_grain(&mut population_manager, &mut resource_manager);
+
+        population_manager.grow(&mut resource_manager);
+        assert_eq!(population_manager.starving(), Some("grain"));
+        assert_close(to_f64(population_manager.change()), -2f64);
+        assert_close(to_f64(resource_manager.count("people")), 8f64);
+
+        // At least a single member starves.
+        let (mut population_manager, mut resource_manager) = managers(2f64);
+        drain_grain(&mut population_manager, &mut resource_manager);
+
+        population_manager.grow(&mut resource_manager);
+        assert_close(to_f64(population_manager.change()), -1f64);
+
+        resource_manager.add_count("grain", to_number(1f64));
+
+        population_manager.grow(&mut resource_manager);
+        assert_eq!(population_manager.starving(), None);
+        assert_close(to_f64(population_manager.change()), 1f64);
+
+    }
+
+}
@@ -0,0 +1,7 @@
+mod asset;
+mod manager;
+mod snapshot;
+
+pub use asset::PopulationAsset;
+pub use manager::PopulationManager;
+pub use snapshot::PopulationSnapshot;
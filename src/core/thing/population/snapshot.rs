@@ -0,0 +1,59 @@
+use crate::core::number::Number;
+use crate::core::thing::building::BuildingManager;
+use crate::core::thing::population::PopulationManager;
+use crate::core::thing::resource::ResourceManager;
+use crate::core::thing::snapshot::ResourceValueSnapshot;
+use serde::Serialize;
+
+/// Snapshot of the population.
+#[derive(Serialize)]
+pub struct PopulationSnapshot {
+
+    /// Name of the resource that counts the population.
+    pub resource: String,
+    /// Population count.
+    pub count: Number,
+    /// Housing capacity.
+    pub capacity: Number,
+    /// Members gained per tick while there is free housing.
+    pub growth: Number,
+    /// Members gained or lost in the last tick.
+    pub change: Number,
+    /// Food eaten by the population per tick.
+    pub food: Vec<ResourceValueSnapshot>,
+    /// Food that has run out, if the population is starving.
+    pub starving: Option<String>,
+
+    /// Members assigned to jobs.
+    pub employed: i32,
+    /// Members without a job.
+    pub unemployed: i32,
+    /// Job slots of all buildings.
+    pub job_slots: i32,
+
+}
+
+impl PopulationSnapshot {
+
+    /// Creates a snapshot of the loaded population.
+    pub fn new(population_manager: &PopulationManager, resource_manager: &ResourceManager, building_manager: &BuildingManager) -> Self {
+
+        let resource = population_manager.resource().unwrap_or_default().to_string();
+        let employed = building_manager.total_workers();
+
+        Self {
+            count: resource_manager.count(&resource),
+            capacity: resource_manager.capacity(&resource),
+            growth: population_manager.calculated_growth(),
+            change: population_manager.change(),
+            food: ResourceValueSnapshot::from_storage(population_manager.calculated_upkeeps()),
+            starving: population_manager.starving().map(|food| food.to_string()),
+            employed,
+            unemployed: (population_manager.count(resource_manager) - employed).max(0),
+            job_slots: building_manager.total_job_slots(),
+            resource,
+        }
+
+    }
+
+}
@@ -0,0 +1,86 @@
+use crate::core::thing::modifier::ModifierScaling;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct PrestigeAsset {
+
+    /// Name of the prestige currency.
+    pub name: String,
+    /// Terms of the currency formula, which are summed.
+    ///
+    /// The formula gives the currency earned over all resets, so a reset gives what was not earned yet.
+    pub formula: Vec<PrestigeTerm>,
+
+}
+
+/// Term of the prestige currency formula, `multiplier × (statistic / divisor) ^ exponent`.
+#[derive(Deserialize)]
+pub struct PrestigeTerm {
+
+    /// Name of the lifetime statistic, such as `resource.gold.produced`.
+    pub statistic: String,
+    /// Divisor of the statistic.
+    #[serde(default = "default_one")]
+    pub divisor: f64,
+    /// Exponent of the divided statistic.
+    #[serde(default = "default_exponent")]
+    pub exponent: f64,
+    /// Multiplier of the term.
+    #[serde(default = "default_one")]
+    pub multiplier: f64,
+
+}
+
+fn default_one() -> f64 {
+
+    1f64
+
+}
+
+fn default_exponent() -> f64 {
+
+    0.5f64
+
+}
+
+#[derive(Deserialize)]
+pub struct PrestigeUpgradeAsset {
+
+    /// Name of the prestige upgrade.
+    pub name: String,
+    /// Category of the prestige upgrade.
+    pub category: String,
+
+    /// Price of the prestige upgrade in the prestige currency.
+    pub price: f64,
+    /// Multiplier of the price for every level.
+    pub price_multiplier: f64,
+    /// Maximum level of the prestige upgrade, `None` if it can be purchased infinitely.
+    #[serde(default = "default_max_level")]
+    pub max_level: Option<i32>,
+
+    /// Modifiers that the prestige upgrade provides.
+    pub modifiers: Vec<PrestigeUpgradeModifier>,
+
+}
+
+fn default_max_level() -> Option<i32> {
+
+    Some(1)
+
+}
+
+#[derive(Deserialize)]
+pub struct PrestigeUpgradeModifier {
+
+    /// Name of the modifier
+    pub name: String,
+    /// Generated value of the modifier.
+    pub value: f64,
+    /// Calculation method of the modifier.
+    pub calculation: String,
+    /// Curve that scales the value by the prestige upgrade level.
+    #[serde(default)]
+    pub scaling: ModifierScaling,
+
+}
@@ -0,0 +1,92 @@
+use crate::core::thing::modifier::{ModifierCalculationMethod, ModifierEntry, ModifierStorage};
+use crate::core::thing::prestige::PrestigeUpgradeAsset;
+use crate::core::number::{to_number, Number};
+
+/// Prestige upgrade, kept through resets.
+pub struct PrestigeUpgrade {
+
+    /// Prestige upgrade's asset.
+    asset: PrestigeUpgradeAsset,
+
+    /// Prestige upgrade level.
+    level: i32,
+
+}
+
+impl From<PrestigeUpgradeAsset> for PrestigeUpgrade {
+
+    /// Creates a prestige upgrade object from an asset.
+    fn from(asset: PrestigeUpgradeAsset) -> Self {
+
+        Self {
+            asset,
+            level: 0,
+        }
+
+    }
+
+}
+
+impl PrestigeUpgrade {
+
+    /// Returns the prestige upgrade's asset.
+    pub fn asset(&self) -> &PrestigeUpgradeAsset {
+
+        &self.asset
+
+    }
+
+    /// Returns level of the prestige upgrade.
+    pub fn level(&self) -> i32 {
+
+        self.level
+
+    }
+
+    /// Sets level of the prestige upgrade, limited by the maximum level.
+    pub fn set_level(&mut self, level: i32) {
+
+        self.level = match self.max_level() {
+            Some(max_level) => level.clamp(0, max_level),
+            None => level.max(0),
+        };
+
+    }
+
+    /// Returns maximum level of the prestige upgrade, `None` if it can be purchased infinitely.
+    pub fn max_level(&self) -> Option<i32> {
+
+        self.asset.max_level.map(|max_level| max_level.max(0))
+
+    }
+
+    /// Has the prestige upgrade reached its maximum level?
+    pub fn is_max_level(&self) -> bool {
+
+        self.max_level().is_some_and(|max_level| self.level >= max_level)
+
+    }
+
+    /// Returns price of the next level, `None` once the maximum level is reached.
+    pub fn price(&self) -> Option<Number> {
+
+        if self.is_max_level() { return None; }
+
+        Some((to_number(self.asset.price) * to_number(self.asset.price_multiplier.max(1f64)).powi(self.level)).max(0f64).floor())
+
+    }
+
+    /// Adds modifiers of the prestige upgrade, scaled by its level.
+    pub fn add_modifiers(&self, modifier_storage: &mut ModifierStorage) {
+
+        if self.level <= 0 { return; }
+
+        for modifier in self.asset.modifiers.iter() {
+
+            modifier_storage.add(ModifierEntry::new(modifier.name.clone(), to_number(modifier.value * modifier.scaling.evaluate(self.level as f64)), ModifierCalculationMethod::from_str(modifier.calculation.as_str())));
+
+        }
+
+    }
+
+}
@@ -0,0 +1,361 @@
+use crate::core::number::{to_finite, to_number, Number};
+use crate::core::thing::modifier::ModifierStorage;
+use crate::core::thing::prestige::{PrestigeAsset, PrestigeSave, PrestigeSnapshot, PrestigeUpgrade, PrestigeUpgradeAsset, PrestigeUpgradeSave, PrestigeUpgradeSnapshot};
+use crate::core::thing::statistics::LifetimeStatistics;
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// Prestige manager.
+///
+/// Keeps the prestige currency and prestige upgrades, which survive resets.
+/// There is no prestige until a prestige asset is loaded.
+pub struct PrestigeManager {
+
+    /// Prestige's asset.
+    asset: Option<PrestigeAsset>,
+    /// Prestige upgrades.
+    upgrades: HashMap<String, PrestigeUpgrade>,
+
+    /// Prestige currency that can be spent.
+    currency: Number,
+    /// Prestige currency earned over all resets.
+    earned: Number,
+    /// Count of resets.
+    resets: u32,
+
+    /// Calculated modifiers of prestige upgrades.
+    calculated_modifiers: ModifierStorage,
+
+}
+
+impl PrestigeManager {
+
+    /// Creates a new prestige manager.
+    pub fn new() -> Self {
+
+        Self {
+            asset: None,
+            upgrades: HashMap::new(),
+            currency: to_number(0f64),
+            earned: to_number(0f64),
+            resets: 0,
+            calculated_modifiers: ModifierStorage::new(),
+        }
+
+    }
+
+    /// Returns permanent modifiers of prestige upgrades.
+    pub fn calculated_modifiers(&self) -> &ModifierStorage {
+
+        &self.calculated_modifiers
+
+    }
+
+    /// Refreshes modifiers of prestige upgrades.
+    fn update_modifiers(&mut self) {
+
+        self.calculated_modifiers.clear();
+
+        for upgrade in self.upgrades.values() {
+
+            upgrade.add_modifiers(&mut self.calculated_modifiers);
+
+        }
+
+    }
+
+}
+
+/// Implementations related to loading prestige.
+impl PrestigeManager {
+
+    /// Loads prestige from string.
+    ///
+    /// # Params
+    ///
+    /// - `prestige_asset_str`: JSON string of prestige asset.
+    pub fn load_from_str(&mut self, prestige_asset_str: &str) -> serde_json::Result<()> {
+
+        let result = serde_json::from_str(prestige_asset_str)?;
+        self.load_from_asset(result);
+        Ok(())
+
+    }
+
+    /// Loads prestige from asset, replacing the previous one.
+    pub fn load_from_asset(&mut self, prestige_asset: PrestigeAsset) {
+
+        self.asset = Some(prestige_asset);
+
+    }
+
+    /// Loads prestige upgrade from string.
+    ///
+    /// # Params
+    ///
+    /// - `prestige_upgrade_asset_str`: JSON string of prestige upgrade asset.
+    pub fn load_upgrade_from_str(&mut self, prestige_upgrade_asset_str: &str) -> serde_json::Result<()> {
+
+        let result = serde_json::from_str(prestige_upgrade_asset_str)?;
+        self.load_upgrade_from_asset(result);
+        Ok(())
+
+    }
+
+    /// Loads prestige upgrade from asset.
+    pub fn load_upgrade_from_asset(&mut self, prestige_upgrade_asset: PrestigeUpgradeAsset) {
+
+        let upgrade = PrestigeUpgrade::from(prestige_upgrade_asset);
+        self.upgrades.insert(upgrade.asset().name.clone(), upgrade);
+        self.update_modifiers();
+
+    }
+
+}
+
+#[derive(Error, Debug)]
+pub enum PrestigeError {
+    #[error("there is no prestige")]
+    NotConfigured,
+    #[error("a reset would not give any '{0}'")]
+    NothingToGain(String),
+}
+
+/// Implementations related to resets.
+impl PrestigeManager {
+
+    /// Returns prestige currency earned over all resets according to the formula, rounded down.
+    ///
+    /// Terms that are not finite, such as a term overflowing `f64` or dividing by 0, are clamped instead of being dropped.
+    fn total(&self, statistics: &LifetimeStatistics) -> Option<Number> {
+
+        let asset = self.asset.as_ref()?;
+
+        let total = asset.formula
+            .iter()
+            .map(|term| {
+
+                let value = to_finite(statistics.value(&term.statistic) / term.divisor).max(0f64);
+                to_finite(value.powf(term.exponent) * term.multiplier)
+
+            })
+            .fold(to_number(0f64), |total, term| to_finite(total + term));
+
+        Some(total.max(0f64).floor())
+
+    }
+
+    /// Returns prestige currency a reset would give right now, 0 if there is no prestige.
+    pub fn preview(&self, statistics: &LifetimeStatistics) -> Number {
+
+        self.total(statistics)
+            .map(|total| (total - self.earned).max(0f64))
+            .unwrap_or(to_number(0f64))
+
+    }
+
+    /// Grants prestige currency for a reset, and returns the gained currency.
+    ///
+    /// Things other than the prestige must be rebuilt by the caller.
+    pub fn reset(&mut self, statistics: &LifetimeStatistics) -> Result<Number, PrestigeError> {
+
+        let asset = self.asset.as_ref().ok_or(PrestigeError::NotConfigured)?;
+        let gained = self.preview(statistics);
+
+        if gained <= 0f64 { return Err(PrestigeError::NothingToGain(asset.name.clone())); }
+
+        self.currency += gained;
+        self.earned += gained;
+        self.resets += 1;
+
+        Ok(gained)
+
+    }
+
+    /// Returns name of the prestige currency, `None` if there is no prestige.
+    pub fn name(&self) -> Option<&str> {
+
+        self.asset.as_ref().map(|asset| asset.name.as_str())
+
+    }
+
+}
+
+#[derive(Error, Debug)]
+pub enum PrestigeUpgradePurchaseError {
+    #[error("prestige upgrade '{0}' does not exist")]
+    NotFound(String),
+    #[error("prestige upgrade '{0}' is already at its maximum level {1}")]
+    MaxLevel(String, i32),
+    #[error("not enough prestige currency")]
+    NotEnoughCurrency,
+}
+
+/// Implementations related to purchasing prestige upgrades.
+impl PrestigeManager {
+
+    /// Purchases the next level of a prestige upgrade, and returns the new level.
+    ///
+    /// The price is taken from the prestige currency.
+    pub fn purchase(&mut self, name: &str) -> Result<i32, PrestigeUpgradePurchaseError> {
+
+        let upgrade = self.upgrades
+            .get_mut(name)
+            .ok_or_else(|| PrestigeUpgradePurchaseError::NotFound(name.to_string()))?;
+
+        let Some(price) = upgrade.price() else {
+
+            return Err(PrestigeUpgradePurchaseError::MaxLevel(name.to_string(), upgrade.level()));
+
+        };
+
+        if self.currency < price { return Err(PrestigeUpgradePurchaseError::NotEnoughCurrency); }
+
+        self.currency -= price;
+        upgrade.set_level(upgrade.level() + 1);
+
+        let level = upgrade.level();
+        self.update_modifiers();
+
+        Ok(level)
+
+    }
+
+}
+
+/// Implementations related to snapshots.
+impl PrestigeManager {
+
+    /// Creates a snapshot of the prestige, `None` if there is no prestige.
+    pub fn snapshot(&self, statistics: &LifetimeStatistics) -> Option<PrestigeSnapshot> {
+
+        let asset = self.asset.as_ref()?;
+
+        let mut upgrades = self.upgrades
+            .values()
+            .map(PrestigeUpgradeSnapshot::from)
+            .collect::<Vec<_>>();
+
+        upgrades.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Some(PrestigeSnapshot {
+            name: asset.name.clone(),
+            currency: self.currency,
+            earned: self.earned,
+            resets: self.resets,
+            preview: self.preview(statistics),
+            upgrades,
+        })
+
+    }
+
+}
+
+/// Implementations related to saves.
+impl PrestigeManager {
+
+    /// Creates a save of the prestige.
+    pub fn save(&self) -> PrestigeSave {
+
+        let mut upgrades = self.upgrades
+            .values()
+            .map(|upgrade| PrestigeUpgradeSave { name: upgrade.asset().name.clone(), level: upgrade.level() })
+            .collect::<Vec<_>>();
+
+        upgrades.sort_by(|a, b| a.name.cmp(&b.name));
+
+        PrestigeSave {
+            currency: self.currency,
+            earned: self.earned,
+            resets: self.resets,
+            upgrades,
+        }
+
+    }
+
+    /// Restores the prestige from a save.
+    ///
+    /// Saves of prestige upgrades that do not exist are ignored.
+    pub fn load_save(&mut self, save: &PrestigeSave) {
+
+        self.currency = to_finite(save.currency).max(0f64);
+        self.earned = to_finite(save.earned).max(0f64);
+        self.resets = save.resets;
+
+        for upgrade_save in save.upgrades.iter() {
+
+            let Some(upgrade) = self.upgrades.get_mut(&upgrade_save.name) else { continue };
+
+            upgrade.set_level(upgrade_save.level);
+
+        }
+
+        self.update_modifiers();
+
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{PrestigeError, PrestigeManager};
+    use crate::core::number::{to_f64, to_number};
+    use crate::core::thing::statistics::LifetimeStatistics;
+
+    fn manager(formula: &str) -> PrestigeManager {
+
+        let mut manager = PrestigeManager::new();
+        manager.load_from_str(&format!(r#"{{"name":"stars","formula":[{formula}]}}"#)).unwrap();
+        manager
+
+    }
+
+    fn statistics(values: &[(&str, f64)]) -> LifetimeStatistics {
+
+        let mut statistics = LifetimeStatistics::new();
+        for (name, value) in values.iter() { statistics.add(name.to_string(), to_number(*value)); }
+        statistics
+
+    }
+
+    #[test]
+    fn resets_give_what_was_not_earned_yet() {
+
+        let mut manager = manager(r#"{"statistic":"gold","divisor":100,"exponent":0.5,"multiplier":2}"#);
+
+        assert!(matches!(manager.reset(&statistics(&[("gold", 20f64)])), Err(PrestigeError::NothingToGain(_))));
+        assert_eq!(to_f64(manager.reset(&statistics(&[("gold", 1e6)])).unwrap()), 200f64);
+        assert_eq!(to_f64(manager.preview(&statistics(&[("gold", 1e6)]))), 0f64);
+
+        // 2 × sqrt(2e4) is 282.8, rounded down.
+        assert!((to_f64(manager.preview(&statistics(&[("gold", 2e6)]))) - 82f64).abs() < 1e-9);
+
+    }
+
+    #[test]
+    fn formula_keeps_large_values() {
+
+        let manager = manager(r#"{"statistic":"gold","exponent":2},{"statistic":"wood","exponent":1}"#);
+        let preview = manager.preview(&statistics(&[("gold", 1e200), ("wood", 1f64)]));
+
+        // 1e400 is beyond `f64`, so it is clamped to the largest `f64` unless big numbers are used.
+        assert!(preview.is_finite());
+        assert!(preview >= 1e300);
+
+        #[cfg(feature = "big_number")]
+        assert_eq!(preview.exponent(), 400);
+
+    }
+
+    #[test]
+    fn terms_that_are_not_finite_are_clamped() {
+
+        let manager = manager(r#"{"statistic":"gold","divisor":0},{"statistic":"wood","exponent":1}"#);
+
+        assert_eq!(to_f64(manager.preview(&statistics(&[("wood", 100f64)]))), 100f64);
+        assert!(manager.preview(&statistics(&[("gold", 5f64), ("wood", 100f64)])) > 1e100);
+
+    }
+
+}
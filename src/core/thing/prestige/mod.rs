@@ -0,0 +1,11 @@
+mod asset;
+mod data;
+mod manager;
+mod save;
+mod snapshot;
+
+pub use asset::{PrestigeAsset, PrestigeUpgradeAsset};
+pub use data::PrestigeUpgrade;
+pub use manager::{PrestigeError, PrestigeManager, PrestigeUpgradePurchaseError};
+pub use save::{PrestigeSave, PrestigeUpgradeSave};
+pub use snapshot::{PrestigeSnapshot, PrestigeUpgradeSnapshot};
@@ -0,0 +1,32 @@
+use crate::core::number::Number;
+use serde::{Deserialize, Serialize};
+
+/// Saved state of the prestige layer.
+#[derive(Serialize, Deserialize, Default)]
+pub struct PrestigeSave {
+
+    /// Prestige currency that can be spent.
+    #[serde(default)]
+    pub currency: Number,
+    /// Prestige currency earned over all resets.
+    #[serde(default)]
+    pub earned: Number,
+    /// Count of resets.
+    #[serde(default)]
+    pub resets: u32,
+    /// Prestige upgrades, sorted by name.
+    #[serde(default)]
+    pub upgrades: Vec<PrestigeUpgradeSave>,
+
+}
+
+/// Saved state of a prestige upgrade.
+#[derive(Serialize, Deserialize)]
+pub struct PrestigeUpgradeSave {
+
+    /// Name of the prestige upgrade.
+    pub name: String,
+    /// Prestige upgrade level.
+    pub level: i32,
+
+}
@@ -0,0 +1,59 @@
+use crate::core::number::Number;
+use crate::core::thing::prestige::PrestigeUpgrade;
+use serde::Serialize;
+
+/// Snapshot of the prestige layer.
+#[derive(Serialize)]
+pub struct PrestigeSnapshot {
+
+    /// Name of the prestige currency.
+    pub name: String,
+    /// Prestige currency that can be spent.
+    pub currency: Number,
+    /// Prestige currency earned over all resets.
+    pub earned: Number,
+    /// Count of resets.
+    pub resets: u32,
+    /// Prestige currency a reset would give right now.
+    pub preview: Number,
+    /// Prestige upgrades, sorted by name.
+    pub upgrades: Vec<PrestigeUpgradeSnapshot>,
+
+}
+
+/// Snapshot of a prestige upgrade.
+#[derive(Serialize)]
+pub struct PrestigeUpgradeSnapshot {
+
+    /// Name of the prestige upgrade.
+    pub name: String,
+    /// Category of the prestige upgrade.
+    pub category: String,
+
+    /// Prestige upgrade level.
+    pub level: i32,
+    /// Maximum level of the prestige upgrade, `None` if it can be purchased infinitely.
+    pub max_level: Option<i32>,
+    /// Has the prestige upgrade reached its maximum level?
+    pub is_max_level: bool,
+    /// Price of the next level, `None` once the maximum level is reached.
+    pub price: Option<Number>,
+
+}
+
+impl From<&PrestigeUpgrade> for PrestigeUpgradeSnapshot {
+
+    fn from(upgrade: &PrestigeUpgrade) -> Self {
+
+        Self {
+            name: upgrade.asset().name.clone(),
+            category: upgrade.asset().category.clone(),
+            level: upgrade.level(),
+            max_level: upgrade.max_level(),
+            is_max_level: upgrade.is_max_level(),
+            price: upgrade.price(),
+        }
+
+    }
+
+}
@@ -0,0 +1,64 @@
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct RecipeAsset {
+
+    /// Name of the recipe.
+    pub name: String,
+    /// Category of the recipe.
+    pub category: String,
+
+    /// Resources consumed by a single craft.
+    pub inputs: Vec<RecipeInput>,
+    /// Resources produced by a single craft.
+    pub outputs: Vec<RecipeOutput>,
+    /// Rules that unlock the recipe. The recipe unlocks when any of them is met.
+    #[serde(default = "default_unlock_rules")]
+    pub unlock_rules: Vec<RecipeUnlockRule>,
+
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RecipeUnlockRule {
+
+    /// Unlocks when every input resource is unlocked.
+    Inputs,
+    /// Unlocks when the resource is unlocked.
+    Resource {
+        /// Resource name.
+        name: String,
+    },
+    /// Unlocks when the upgrade is bought.
+    Upgrade {
+        /// Upgrade name.
+        name: String,
+    },
+
+}
+
+fn default_unlock_rules() -> Vec<RecipeUnlockRule> {
+
+    vec![RecipeUnlockRule::Inputs]
+
+}
+
+#[derive(Deserialize)]
+pub struct RecipeInput {
+
+    /// Resource name.
+    pub name: String,
+    /// Resource value.
+    pub value: f64,
+
+}
+
+#[derive(Deserialize)]
+pub struct RecipeOutput {
+
+    /// Resource name.
+    pub name: String,
+    /// Resource value.
+    pub value: f64,
+
+}
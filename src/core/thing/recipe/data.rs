@@ -0,0 +1,129 @@
+use super::RecipeAsset;
+use crate::core::thing::modifier::{ModifierCalculationMethod, ModifierStorage};
+use crate::core::thing::resource::ResourceStorage;
+use crate::core::number::{to_number, Number};
+
+/// Recipe
+pub struct Recipe {
+
+    /// Recipe's asset.
+    asset: RecipeAsset,
+
+    /// Calculated inputs of a single craft.
+    calculated_inputs: ResourceStorage,
+    /// Calculated outputs of a single craft.
+    calculated_outputs: ResourceStorage,
+
+    /// Is the recipe unlocked?
+    is_unlocked: bool,
+
+}
+
+impl From<RecipeAsset> for Recipe {
+
+    /// Creates a recipe object from an asset.
+    fn from(asset: RecipeAsset) -> Self {
+
+        Self {
+            asset,
+            calculated_inputs: ResourceStorage::new(),
+            calculated_outputs: ResourceStorage::new(),
+            is_unlocked: false,
+        }
+
+    }
+
+}
+
+impl Recipe {
+
+    /// Returns the recipe's asset.
+    pub fn asset(&self) -> &RecipeAsset {
+
+        &self.asset
+
+    }
+
+    /// Is the recipe unlocked?
+    pub fn is_unlocked(&self) -> bool {
+
+        self.is_unlocked
+
+    }
+
+    /// Unlocks the recipe.
+    pub fn unlock(&mut self) {
+
+        self.is_unlocked = true;
+
+    }
+
+}
+
+/// Implementations related to recipe's calculation.
+impl Recipe {
+
+    /// Calculates recipe's inputs and outputs.
+    pub fn calculate(&mut self, modifier_storage: &ModifierStorage) {
+
+        self.calculated_inputs.clear();
+        self.calculated_outputs.clear();
+
+        for input in self.asset.inputs.iter() {
+
+            self.calculated_inputs.add(input.name.to_string(), self.create_modified_value(input.value, "input", modifier_storage));
+
+        }
+
+        for output in self.asset.outputs.iter() {
+
+            self.calculated_outputs.add(output.name.to_string(), self.create_modified_value(output.value, "output", modifier_storage));
+
+        }
+
+    }
+
+    /// Calculated inputs of a single craft.
+    pub fn calculated_inputs(&self) -> &ResourceStorage {
+
+        &self.calculated_inputs
+
+    }
+
+    /// Calculated outputs of a single craft.
+    pub fn calculated_outputs(&self) -> &ResourceStorage {
+
+        &self.calculated_outputs
+
+    }
+
+    /// Creates input or output value using modifiers.
+    ///
+    /// # Params
+    ///
+    /// - `kind`: `input` or `output`.
+    fn create_modified_value(&self, original_value: f64, kind: &str, modifier_storage: &ModifierStorage) -> Number {
+
+        let name_key = format!("recipe.name.{}.{}", self.asset.name, kind);
+        let category_key = format!("recipe.category.{}.{}", self.asset.category, kind);
+        let global_key = format!("recipe.global.{}", kind);
+
+        let mut value = to_number(original_value);
+        value += 0f64 +
+            modifier_storage.value(&name_key, ModifierCalculationMethod::Base) +
+            modifier_storage.value(&category_key, ModifierCalculationMethod::Base) +
+            modifier_storage.value(&global_key, ModifierCalculationMethod::Base);
+        value *= 1f64 +
+            modifier_storage.value(&name_key, ModifierCalculationMethod::Additive) +
+            modifier_storage.value(&category_key, ModifierCalculationMethod::Additive) +
+            modifier_storage.value(&global_key, ModifierCalculationMethod::Additive);
+        value += 0f64 +
+            modifier_storage.value(&name_key, ModifierCalculationMethod::Flat) +
+            modifier_storage.value(&category_key, ModifierCalculationMethod::Flat) +
+            modifier_storage.value(&global_key, ModifierCalculationMethod::Flat);
+
+        value.max(0f64)
+
+    }
+
+}
@@ -0,0 +1,293 @@
+use crate::core::thing::modifier::ModifierStorage;
+use crate::core::thing::recipe::{Recipe, RecipeAsset, RecipeSnapshot, RecipeUnlockRule};
+use crate::core::thing::resource::ResourceManager;
+use crate::core::thing::upgrade::UpgradeManager;
+use std::collections::HashMap;
+use thiserror::Error;
+use crate::core::number::to_f64;
+
+/// Recipe manager.
+pub struct RecipeManager {
+
+    /// Recipes.
+    recipes: HashMap<String, Recipe>,
+
+}
+
+impl RecipeManager {
+
+    /// Creates a new recipe manager.
+    pub fn new() -> Self {
+
+        Self {
+            recipes: HashMap::new(),
+        }
+
+    }
+
+}
+
+/// Implementations related to loading and registering recipes.
+impl RecipeManager {
+
+    /// Loads recipe from string.
+    ///
+    /// # Params
+    ///
+    /// - `recipe_asset_str`: JSON string of recipe asset.
+    pub fn load_from_str(&mut self, recipe_asset_str: &str) -> serde_json::Result<()> {
+
+        let result = serde_json::from_str(recipe_asset_str)?;
+        self.load_from_asset(result);
+        Ok(())
+
+    }
+
+    /// Loads recipe from asset.
+    pub fn load_from_asset(&mut self, recipe_asset: RecipeAsset) {
+
+        let recipe = Recipe::from(recipe_asset);
+        self.add(recipe);
+
+    }
+
+    /// Adds a new recipe.
+    ///
+    /// The recipe is calculated without modifiers, so it can be crafted before the first tick.
+    pub fn add(&mut self, mut recipe: Recipe) {
+
+        recipe.calculate(&ModifierStorage::new());
+        self.recipes.insert(recipe.asset().name.clone(), recipe);
+
+    }
+
+}
+
+/// Implementations related to calculation.
+impl RecipeManager {
+
+    /// Calculates all recipes.
+    ///
+    /// # Params
+    ///
+    /// - `modifier_storage`: Modifiers that can be used for calculating recipes.
+    pub fn calculate(&mut self, modifier_storage: &ModifierStorage) {
+
+        for (_, recipe) in self.recipes.iter_mut() {
+
+            recipe.calculate(modifier_storage);
+
+        }
+
+    }
+
+}
+
+/// Implementations related to unlocking recipes.
+impl RecipeManager {
+
+    /// Unlocks recipes whose unlock rules are met.
+    ///
+    /// Returns names of the newly unlocked recipes.
+    pub fn update_unlocks(&mut self, resource_manager: &ResourceManager, upgrade_manager: &UpgradeManager) -> Vec<String> {
+
+        let mut unlocked = Vec::new();
+
+        for (name, recipe) in self.recipes.iter_mut() {
+
+            if recipe.is_unlocked() { continue; }
+
+            let is_met = recipe.asset()
+                .unlock_rules
+                .iter()
+                .any(|rule| match rule {
+                    RecipeUnlockRule::Inputs => recipe.asset().inputs.iter().all(|input| resource_manager.is_unlocked(&input.name)),
+                    RecipeUnlockRule::Resource { name } => resource_manager.is_unlocked(name),
+                    RecipeUnlockRule::Upgrade { name } => upgrade_manager.count(name) > 0,
+                });
+
+            if is_met {
+
+                recipe.unlock();
+                unlocked.push(name.clone());
+
+            }
+
+        }
+
+        unlocked.sort();
+        unlocked
+
+    }
+
+}
+
+#[derive(Error, Debug)]
+pub enum RecipeCraftError {
+    #[error("recipe '{0}' does not exist")]
+    NotFound(String),
+    #[error("recipe '{0}' is locked")]
+    Locked(String),
+    #[error("not enough resource '{0}'")]
+    NotEnoughResource(String),
+}
+
+/// Implementations related to crafting.
+impl RecipeManager {
+
+    /// Crafts the recipe.
+    ///
+    /// Inputs are taken from the resources and outputs are added to them.
+    /// Outputs above the capacity are lost.
+    pub fn craft(&self, name: &str, count: u32, resource_manager: &mut ResourceManager) -> Result<u32, RecipeCraftError> {
+
+        let recipe = self.recipes
+            .get(name)
+            .ok_or_else(|| RecipeCraftError::NotFound(name.to_string()))?;
+
+        if !recipe.is_unlocked() { return Err(RecipeCraftError::Locked(name.to_string())); }
+
+        if let Some((resource, _)) = recipe.calculated_inputs()
+            .iter()
+            .find(|(resource, value)| resource_manager.count(resource) < **value * count as f64) {
+
+            return Err(RecipeCraftError::NotEnoughResource(resource.clone()));
+
+        }
+
+        for (resource, value) in recipe.calculated_inputs().iter() {
+
+            resource_manager.add_count(resource, -*value * count as f64);
+
+        }
+
+        for (resource, value) in recipe.calculated_outputs().iter() {
+
+            resource_manager.add_count(resource, *value * count as f64);
+
+        }
+
+        Ok(count)
+
+    }
+
+    /// Crafts the recipe as many times as possible.
+    ///
+    /// The count is limited by the inputs and by the capacity left for the outputs.
+    pub fn craft_max(&self, name: &str, resource_manager: &mut ResourceManager) -> Result<u32, RecipeCraftError> {
+
+        let count = self.max_craft_count(name, resource_manager)?;
+        if count == 0 { return Ok(0); }
+
+        self.craft(name, count, resource_manager)
+
+    }
+
+    /// Returns how many times the recipe can be crafted right now.
+    pub fn max_craft_count(&self, name: &str, resource_manager: &ResourceManager) -> Result<u32, RecipeCraftError> {
+
+        let recipe = self.recipes
+            .get(name)
+            .ok_or_else(|| RecipeCraftError::NotFound(name.to_string()))?;
+
+        let input_limit = recipe.calculated_inputs()
+            .iter()
+            .filter(|(_, value)| **value > 0f64)
+            .map(|(resource, value)| to_f64(resource_manager.count(resource) / *value).floor());
+
+        let output_limit = recipe.calculated_outputs()
+            .iter()
+            .filter(|(resource, value)| **value > 0f64 && !resource_manager.is_flow(resource))
+            .map(|(resource, value)| to_f64((resource_manager.capacity(resource) - resource_manager.count(resource)) / *value).floor());
+
+        let count = input_limit
+            .chain(output_limit)
+            .reduce(f64::min)
+            .unwrap_or(0f64);
+
+        Ok(count.clamp(0f64, u32::MAX as f64) as u32)
+
+    }
+
+}
+
+/// Implementations related to snapshots.
+impl RecipeManager {
+
+    /// Creates snapshots of recipes, sorted by name.
+    pub fn snapshot(&self) -> Vec<RecipeSnapshot> {
+
+        let mut snapshots = self.recipes
+            .values()
+            .map(RecipeSnapshot::from)
+            .collect::<Vec<_>>();
+
+        snapshots.sort_by(|a, b| a.name.cmp(&b.name));
+        snapshots
+
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{RecipeCraftError, RecipeManager};
+    use crate::core::number::{to_f64, to_number};
+    use crate::core::thing::building::BuildingManager;
+    use crate::core::thing::resource::ResourceManager;
+    use crate::core::thing::upgrade::UpgradeManager;
+
+    /// Creates a beam recipe and resources with 250 unlocked wood and room for 2 beams.
+    fn setup() -> (RecipeManager, ResourceManager) {
+
+        let mut recipe_manager = RecipeManager::new();
+        recipe_manager.load_from_str(r#"{"name":"beam","category":"wood","inputs":[{"name":"wood","value":100}],"outputs":[{"name":"beam","value":1}]}"#).unwrap();
+
+        let mut resource_manager = ResourceManager::new();
+        resource_manager.load_from_str(r#"{"name":"wood","category":"raw","base_capacity":1000,"modifiers":[]}"#).unwrap();
+        resource_manager.load_from_str(r#"{"name":"beam","category":"crafted","base_capacity":2,"modifiers":[]}"#).unwrap();
+        resource_manager.add_count("wood", to_number(250f64));
+        resource_manager.update_unlocks(&BuildingManager::new(), &UpgradeManager::new());
+
+        (recipe_manager, resource_manager)
+
+    }
+
+    #[test]
+    fn locked_recipe_can_not_be_crafted() {
+
+        let (recipe_manager, mut resource_manager) = setup();
+
+        assert!(matches!(recipe_manager.craft("beam", 1, &mut resource_manager), Err(RecipeCraftError::Locked(_))));
+        assert_eq!(to_f64(resource_manager.count("wood")), 250f64);
+
+    }
+
+    #[test]
+    fn recipe_unlocks_with_its_inputs_and_crafts_before_the_first_tick() {
+
+        let (mut recipe_manager, mut resource_manager) = setup();
+
+        assert_eq!(recipe_manager.update_unlocks(&resource_manager, &UpgradeManager::new()), vec!["beam".to_string()]);
+        assert_eq!(recipe_manager.craft("beam", 1, &mut resource_manager).unwrap(), 1);
+        assert_eq!(to_f64(resource_manager.count("wood")), 150f64);
+        assert_eq!(to_f64(resource_manager.count("beam")), 1f64);
+
+    }
+
+    #[test]
+    fn craft_max_is_limited_by_inputs_and_output_capacity() {
+
+        let (mut recipe_manager, mut resource_manager) = setup();
+        recipe_manager.update_unlocks(&resource_manager, &UpgradeManager::new());
+
+        assert_eq!(recipe_manager.max_craft_count("beam", &resource_manager).unwrap(), 2);
+
+        resource_manager.add_count("beam", to_number(1f64));
+        assert_eq!(recipe_manager.craft_max("beam", &mut resource_manager).unwrap(), 1);
+        assert_eq!(to_f64(resource_manager.count("wood")), 150f64);
+
+    }
+
+}
@@ -0,0 +1,9 @@
+mod asset;
+mod data;
+mod manager;
+mod snapshot;
+
+pub use asset::{RecipeAsset, RecipeUnlockRule};
+pub use data::Recipe;
+pub use manager::{RecipeCraftError, RecipeManager};
+pub use snapshot::RecipeSnapshot;
@@ -0,0 +1,38 @@
+use crate::core::thing::recipe::Recipe;
+use crate::core::thing::snapshot::ResourceValueSnapshot;
+use serde::Serialize;
+
+/// Snapshot of a recipe.
+#[derive(Serialize)]
+pub struct RecipeSnapshot {
+
+    /// Name of the recipe.
+    pub name: String,
+    /// Category of the recipe.
+    pub category: String,
+
+    /// Inputs of a single craft.
+    pub inputs: Vec<ResourceValueSnapshot>,
+    /// Outputs of a single craft.
+    pub outputs: Vec<ResourceValueSnapshot>,
+
+    /// Is the recipe unlocked?
+    pub is_unlocked: bool,
+
+}
+
+impl From<&Recipe> for RecipeSnapshot {
+
+    fn from(recipe: &Recipe) -> Self {
+
+        Self {
+            name: recipe.asset().name.clone(),
+            category: recipe.asset().category.clone(),
+            inputs: ResourceValueSnapshot::from_storage(recipe.calculated_inputs()),
+            outputs: ResourceValueSnapshot::from_storage(recipe.calculated_outputs()),
+            is_unlocked: recipe.is_unlocked(),
+        }
+
+    }
+
+}
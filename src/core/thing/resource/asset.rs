@@ -1,4 +1,4 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize)]
 pub struct ResourceAsset {
@@ -7,11 +7,82 @@ pub struct ResourceAsset {
     pub name: String,
     /// Category of the resource.
     pub category: String,
+    /// Kind of the resource.
+    #[serde(default)]
+    pub kind: ResourceKind,
 
-    /// Base capacity value.
+    /// Base capacity value. Flow resources have no capacity.
     pub base_capacity: f64,
     /// Modifiers that resource passively generates.
     pub modifiers: Vec<ResourceModifiers>,
+    /// Decay of the stock.
+    #[serde(default)]
+    pub decay: ResourceDecay,
+    /// Resource that receives a part of the amount above the capacity.
+    #[serde(default)]
+    pub overflow: Option<ResourceOverflow>,
+    /// Rules that unlock the resource. The resource unlocks when any of them is met.
+    #[serde(default = "default_unlock_rules")]
+    pub unlock_rules: Vec<ResourceUnlockRule>,
+
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResourceUnlockRule {
+
+    /// Unlocks when the resource count first goes above zero.
+    Count,
+    /// Unlocks when a building that produces or stores the resource is unlocked.
+    Building,
+    /// Unlocks when the upgrade is bought.
+    Upgrade {
+        /// Upgrade name.
+        name: String,
+    },
+
+}
+
+fn default_unlock_rules() -> Vec<ResourceUnlockRule> {
+
+    vec![ResourceUnlockRule::Count]
+
+}
+
+#[derive(Deserialize, Serialize, Copy, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ResourceKind {
+
+    /// Resource accumulates up to its capacity, such as wood.
+    #[default]
+    Stockpile,
+    /// Resource is balanced every tick as supply against demand, such as power.
+    Flow,
+
+}
+
+#[derive(Deserialize, Default)]
+pub struct ResourceDecay {
+
+    /// Ratio of the stock above the threshold lost per tick.
+    #[serde(default)]
+    pub ratio: f64,
+    /// Flat amount lost per tick.
+    #[serde(default)]
+    pub flat: f64,
+    /// Stock at or below the threshold does not decay.
+    #[serde(default)]
+    pub threshold: f64,
+
+}
+
+#[derive(Deserialize)]
+pub struct ResourceOverflow {
+
+    /// Resource name.
+    pub name: String,
+    /// Ratio of the overflow converted into the resource.
+    pub ratio: f64,
 
 }
 
@@ -24,5 +95,25 @@ pub struct ResourceModifiers {
     pub value: f64,
     /// Calculation method of the modifier.
     pub calculation: String,
+    /// How the value scales with the resource count.
+    #[serde(default)]
+    pub scaling: ResourceModifierScaling,
+    /// Maximum absolute value of the modifier.
+    #[serde(default)]
+    pub cap: Option<f64>,
+
+}
+
+#[derive(Deserialize, Copy, Clone, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ResourceModifierScaling {
+
+    /// Value is generated as is.
+    #[default]
+    Fixed,
+    /// Value is generated per unit of the resource.
+    PerUnit,
+    /// Value is multiplied by the ratio of count to capacity.
+    PerCapacity,
 
 }
@@ -1,5 +1,7 @@
 use crate::core::thing::modifier::{ModifierCalculationMethod, ModifierEntry, ModifierStorage};
-use crate::core::thing::resource::asset::ResourceAsset;
+use crate::core::thing::resource::asset::{ResourceAsset, ResourceKind, ResourceModifierScaling, ResourceModifiers};
+use crate::core::thing::resource::statistics::{RateWindow, ResourceStatistics};
+use crate::core::number::{to_f64, to_number, Number};
 
 /// Resource
 pub struct Resource {
@@ -8,16 +10,30 @@ pub struct Resource {
     asset: ResourceAsset,
 
     /// Resource count.
-    count: f64,
+    count: Number,
     /// Capacity of the resource.
-    capacity: f64,
+    capacity: Number,
     /// Resource production per tick.
-    production: f64,
+    production: Number,
     /// Resource consumption per tick.
-    consumption: f64,
+    consumption: Number,
+    /// Resource lost to decay in the last tick.
+    decay: Number,
+    /// Resource above the capacity since the last tick.
+    overflow: Number,
+    /// Resource above the capacity in the last tick.
+    wasted: Number,
+    /// Resource count when the production of the tick started.
+    tick_start_count: Number,
+    /// History of net change per tick.
+    statistics: ResourceStatistics,
 
     /// Calculated modifiers generated from the resource.
     calculated_modifiers: ModifierStorage,
+    /// Count and capacity used for the last calculation.
+    calculated_state: Option<(Number, Number)>,
+    /// Does any modifier depend on the count?
+    has_scaled_modifiers: bool,
 
     /// Is the resource unlocked?
     is_unlocked: bool,
@@ -30,11 +46,18 @@ impl From<ResourceAsset> for Resource {
     fn from(asset: ResourceAsset) -> Self {
 
         Resource {
-            count: 0f64,
-            capacity: asset.base_capacity,
-            production: 0f64,
-            consumption: 0f64,
+            count: to_number(0f64),
+            capacity: to_number(asset.base_capacity),
+            production: to_number(0f64),
+            consumption: to_number(0f64),
+            decay: to_number(0f64),
+            overflow: to_number(0f64),
+            wasted: to_number(0f64),
+            tick_start_count: to_number(0f64),
+            statistics: ResourceStatistics::new(),
             calculated_modifiers: ModifierStorage::new(),
+            calculated_state: None,
+            has_scaled_modifiers: asset.modifiers.iter().any(|m| m.scaling != ResourceModifierScaling::Fixed),
             is_unlocked: false,
             asset,
         }
@@ -52,39 +75,48 @@ impl Resource {
 
     }
 
+    /// Is the resource a flow resource?
+    pub fn is_flow(&self) -> bool {
+
+        self.asset.kind == ResourceKind::Flow
+
+    }
+
     /// Returns the resource's count.
-    pub fn count(&self) -> f64 {
+    pub fn count(&self) -> Number {
         
         self.count
         
     }
 
     /// Adds to the resource's count.
-    pub fn add_count(&mut self, count: f64) {
+    ///
+    /// Anything above the capacity is recorded as overflow.
+    pub fn add_count(&mut self, count: Number) {
 
-        if self.count >= self.capacity && count >= 0f64 { return; }
-        else if self.count + count >= self.capacity {self.count = self.capacity; }
+        if self.count >= self.capacity && count >= 0f64 { self.overflow += count; }
+        else if self.count + count >= self.capacity { self.overflow += self.count + count - self.capacity; self.count = self.capacity; }
         else if self.count + count > 0f64 { self.count += count; }
-        else { self.count = 0f64; }
+        else { self.count = to_number(0f64); }
 
     }
 
     /// Sets the resource's count.
-    pub fn set_count(&mut self, count: f64) {
+    pub fn set_count(&mut self, count: Number) {
 
         self.count = count;
 
     }
     
     /// Sets the resource's capacity.
-    pub fn capacity(&self) -> f64 {
+    pub fn capacity(&self) -> Number {
         
         self.capacity
         
     }
 
     /// Sets the resource's capacity.
-    pub fn set_capacity(&mut self, capacity: f64) {
+    pub fn set_capacity(&mut self, capacity: Number) {
 
         self.capacity = (self.asset.base_capacity + capacity).max(self.asset.base_capacity);
 
@@ -110,49 +142,205 @@ impl Resource {
 impl Resource {
 
     /// Returns resource's production.
-    pub fn production(&self) -> f64 {
+    pub fn production(&self) -> Number {
 
         self.production
 
     }
 
     /// Sets resource's production.
-    pub fn set_production(&mut self, production: f64) {
+    pub fn set_production(&mut self, production: Number) {
 
         self.production = production;
 
     }
 
     /// Returns resource's consumption.
-    pub fn consumption(&self) -> f64 {
+    pub fn consumption(&self) -> Number {
 
         self.consumption
 
     }
 
     /// Sets resource's consumption.
-    pub fn set_consumption(&mut self, consumption: f64) {
+    pub fn set_consumption(&mut self, consumption: Number) {
 
         self.consumption = consumption;
 
     }
 
-    /// Produces resource.
-    pub fn produce(&mut self) {
+    /// Returns resource lost to decay in the last tick.
+    pub fn decay(&self) -> Number {
+
+        self.decay
+
+    }
+
+    /// Produces resource, then applies decay to the stock.
+    ///
+    /// Flow resources do not accumulate.
+    pub fn produce(&mut self, modifier_storage: &ModifierStorage) {
+
+        if self.is_flow() { return; }
+
+        self.tick_start_count = self.count;
+
+        self.add_count(self.production - self.consumption);
+
+        self.decay = self.create_modified_decay_value(modifier_storage);
+        self.count -= self.decay;
+
+        self.wasted = self.overflow;
+        self.overflow = to_number(0f64);
+
+    }
+
+    /// Records changes of the tick in the rate history.
+    ///
+    /// Must be called at the end of the tick, so every change since `produce` is included.
+    pub fn record_statistics(&mut self) {
 
-        let value = self.production - self.consumption;
+        if self.is_flow() { return; }
 
-        if self.count >= self.capacity && value >= 0f64 { return }
-        else if self.count + value >= self.capacity { self.count = self.capacity; }
-        else if self.count + value > 0f64 { self.count += value; }
-        else { self.count = 0f64;  }
+        self.statistics.record(to_f64(self.count - self.tick_start_count), to_f64(self.decay), to_f64(self.wasted));
+
+    }
+
+    /// Returns resource above the capacity in the last tick.
+    ///
+    /// Includes the resource converted to other resources.
+    pub fn wasted(&self) -> Number {
+
+        self.wasted
+
+    }
+
+    /// Creates decay value of the current stock using modifiers.
+    fn create_modified_decay_value(&self, modifier_storage: &ModifierStorage) -> Number {
+
+        let decay = &self.asset.decay;
+        let decaying_count = (self.count - decay.threshold.max(0f64)).max(0f64);
+
+        if decaying_count == 0f64 { return decaying_count; }
+
+        let mut value = decaying_count * decay.ratio + decay.flat;
+        value += 0f64 +
+            modifier_storage.value(&format!("resource.name.{}.decay", self.asset.name), ModifierCalculationMethod::Base) +
+            modifier_storage.value(&format!("resource.category.{}.decay", self.asset.category), ModifierCalculationMethod::Base) +
+            modifier_storage.value("resource.global.decay", ModifierCalculationMethod::Base);
+        value *= 1f64 +
+            modifier_storage.value(&format!("resource.name.{}.decay", self.asset.name), ModifierCalculationMethod::Additive) +
+            modifier_storage.value(&format!("resource.category.{}.decay", self.asset.category), ModifierCalculationMethod::Additive) +
+            modifier_storage.value("resource.global.decay", ModifierCalculationMethod::Additive);
+        value += 0f64 +
+            modifier_storage.value(&format!("resource.name.{}.decay", self.asset.name), ModifierCalculationMethod::Flat) +
+            modifier_storage.value(&format!("resource.category.{}.decay", self.asset.category), ModifierCalculationMethod::Flat) +
+            modifier_storage.value("resource.global.decay", ModifierCalculationMethod::Flat);
+
+        value.clamp(0f64, decaying_count)
 
     }
 
     /// Returns true if resource is 0 and consumption is bigger than production.
+    ///
+    /// Flow resources are never drained, buildings using them are throttled instead.
     pub fn is_drained(&self) -> bool {
 
-        self.consumption > self.production && self.count == 0f64
+        self.is_drained_with(to_number(0f64))
+
+    }
+
+    /// Returns true if the resource would be drained with the additional consumption per tick.
+    pub fn is_drained_with(&self, demand: Number) -> bool {
+
+        !self.is_flow() && self.consumption + demand > self.production && self.count == 0f64
+
+    }
+
+    /// Returns true if the flow resource is demanded but not supplied at all.
+    pub fn is_unsupplied(&self) -> bool {
+
+        self.is_unsupplied_with(to_number(0f64))
+
+    }
+
+    /// Returns true if the flow resource would be demanded with the additional consumption per tick but is not supplied at all.
+    pub fn is_unsupplied_with(&self, demand: Number) -> bool {
+
+        self.is_flow() && self.consumption + demand > 0f64 && self.production <= 0f64
+
+    }
+
+    /// Returns flow resource's supply per tick.
+    pub fn supply(&self) -> Number {
+
+        self.production
+
+    }
+
+    /// Returns flow resource's demand per tick.
+    pub fn demand(&self) -> Number {
+
+        self.consumption
+
+    }
+
+    /// Returns ratio of the demand that the supply covers.
+    pub fn utilization(&self) -> f64 {
+
+        flow_utilization(self.supply(), self.demand())
+
+    }
+
+}
+
+/// Implementations related to resource statistics.
+impl Resource {
+
+    /// Returns average net change per tick in the window.
+    pub fn average_rate(&self, window: RateWindow) -> f64 {
+
+        self.statistics.average(window)
+
+    }
+
+    /// Returns average amount lost to decay per tick in the window.
+    pub fn average_decay(&self, window: RateWindow) -> f64 {
+
+        self.statistics.average_decay(window)
+
+    }
+
+    /// Returns average amount above the capacity per tick in the window.
+    pub fn average_wasted(&self, window: RateWindow) -> f64 {
+
+        self.statistics.average_wasted(window)
+
+    }
+
+    /// Returns estimated ticks until the resource reaches its capacity.
+    ///
+    /// Returns `None` if the resource is not growing.
+    pub fn ticks_until_capacity(&self, window: RateWindow) -> Option<f64> {
+
+        let rate = self.average_rate(window);
+
+        if self.count >= self.capacity { Some(0f64) }
+        else if rate > 0f64 { Some(to_f64((self.capacity - self.count) / rate).ceil()) }
+        else { None }
+
+    }
+
+    /// Returns estimated ticks until the resource runs empty.
+    ///
+    /// Returns `None` if the resource is not shrinking.
+    pub fn ticks_until_empty(&self, window: RateWindow) -> Option<f64> {
+
+        let rate = self.average_rate(window);
+
+        if self.count <= 0f64 { Some(0f64) }
+        else if rate < 0f64 { Some(to_f64(self.count / -rate).ceil()) }
+        else { None }
 
     }
 
@@ -162,16 +350,48 @@ impl Resource {
 impl Resource {
 
     /// Calculates resource's modifiers.
+    ///
+    /// Modifiers are only recalculated when they can change, which is when the count or the capacity changes
+    /// and a modifier scales with them.
     pub fn calculate(&mut self) {
 
+        let state = (self.count, self.capacity);
+
+        if let Some(calculated_state) = self.calculated_state {
+
+            if !self.has_scaled_modifiers || calculated_state == state { return; }
+
+        }
+
         self.calculated_modifiers.clear();
+        self.calculated_state = Some(state);
 
         self.asset.modifiers.iter().for_each(|m| {
-            self.calculated_modifiers.add(ModifierEntry::new(m.name.clone(), m.value, ModifierCalculationMethod::from_str(m.calculation.as_str())));
+            self.calculated_modifiers.add(ModifierEntry::new(m.name.clone(), Self::create_scaled_modifier_value(m, state.0, state.1), ModifierCalculationMethod::from_str(m.calculation.as_str())));
         });
 
     }
 
+    /// Creates modifier value scaled by the resource's count.
+    fn create_scaled_modifier_value(modifier: &ResourceModifiers, count: Number, capacity: Number) -> Number {
+
+        let value = match modifier.scaling {
+            ResourceModifierScaling::Fixed => to_number(modifier.value),
+            ResourceModifierScaling::PerUnit => modifier.value * count,
+            ResourceModifierScaling::PerCapacity => {
+
+                if capacity > 0f64 { modifier.value * count / capacity } else { to_number(0f64) }
+
+            },
+        };
+
+        match modifier.cap {
+            Some(cap) => value.clamp(-cap.abs(), cap.abs()),
+            None => value,
+        }
+
+    }
+
     /// Calculated resource's modifiers.
     pub fn calculated_modifiers(&self) -> &ModifierStorage {
 
@@ -180,3 +400,104 @@ impl Resource {
     }
 
 }
+
+/// Returns ratio of the demand that the supply covers, between 0 and 1.
+pub fn flow_utilization(supply: Number, demand: Number) -> f64 {
+
+    if demand <= 0f64 { 1f64 } else { to_f64(supply / demand).clamp(0f64, 1f64) }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{flow_utilization, Resource};
+    use crate::core::number::{to_f64, to_number};
+    use crate::core::thing::modifier::ModifierCalculationMethod;
+    use crate::core::thing::resource::asset::ResourceAsset;
+
+    #[test]
+    fn utilization_is_the_covered_ratio_of_the_demand() {
+
+        assert_eq!(flow_utilization(to_number(5f64), to_number(10f64)), 0.5f64);
+        assert_eq!(flow_utilization(to_number(10f64), to_number(10f64)), 1f64);
+        assert_eq!(flow_utilization(to_number(0f64), to_number(10f64)), 0f64);
+
+    }
+
+    #[test]
+    fn utilization_is_full_without_demand_or_with_surplus() {
+
+        assert_eq!(flow_utilization(to_number(0f64), to_number(0f64)), 1f64);
+        assert_eq!(flow_utilization(to_number(20f64), to_number(10f64)), 1f64);
+
+    }
+
+    /// Creates a resource with a capacity of 100 that generates the modifier.
+    fn resource(modifier: &str) -> Resource {
+
+        Resource::from(serde_json::from_str::<ResourceAsset>(&format!(r#"{{"name":"gold","category":"raw","base_capacity":100,"modifiers":[{modifier}]}}"#)).unwrap())
+
+    }
+
+    fn modifier(resource: &mut Resource) -> f64 {
+
+        resource.calculate();
+        to_f64(resource.calculated_modifiers().value("global.speed", ModifierCalculationMethod::Additive))
+
+    }
+
+    #[test]
+    fn per_unit_modifiers_scale_with_count_up_to_the_cap() {
+
+        let mut resource = resource(r#"{"name":"global.speed","value":0.01,"calculation":"additive","scaling":"per_unit","cap":0.6}"#);
+
+        resource.set_count(to_number(50f64));
+        assert!((modifier(&mut resource) - 0.5).abs() < 1e-9);
+
+        resource.set_count(to_number(80f64));
+        assert!((modifier(&mut resource) - 0.6).abs() < 1e-9);
+
+    }
+
+    #[test]
+    fn per_capacity_modifiers_scale_with_fill_ratio() {
+
+        let mut resource = resource(r#"{"name":"global.speed","value":2,"calculation":"additive","scaling":"per_capacity"}"#);
+
+        resource.set_count(to_number(25f64));
+        assert!((modifier(&mut resource) - 0.5).abs() < 1e-9);
+
+        // Doubling the capacity halves the fill ratio.
+        resource.set_capacity(to_number(100f64));
+        assert!((modifier(&mut resource) - 0.25).abs() < 1e-9);
+
+    }
+
+    #[test]
+    fn calculated_modifiers_are_refreshed_when_the_count_changes() {
+
+        let mut resource = resource(r#"{"name":"global.speed","value":1,"calculation":"additive","scaling":"per_unit"}"#);
+
+        resource.set_count(to_number(10f64));
+        assert_eq!(modifier(&mut resource), 10f64);
+        assert_eq!(resource.calculated_state, Some((to_number(10f64), to_number(100f64))));
+
+        resource.add_count(to_number(5f64));
+        assert_eq!(modifier(&mut resource), 15f64);
+        assert_eq!(resource.calculated_state, Some((to_number(15f64), to_number(100f64))));
+
+    }
+
+    #[test]
+    fn fixed_modifiers_do_not_scale() {
+
+        let mut resource = resource(r#"{"name":"global.speed","value":0.3,"calculation":"additive"}"#);
+
+        assert_eq!(modifier(&mut resource), 0.3);
+        resource.set_count(to_number(90f64));
+        assert_eq!(modifier(&mut resource), 0.3);
+
+    }
+
+}
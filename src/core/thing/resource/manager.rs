@@ -1,6 +1,9 @@
+use crate::core::thing::building::BuildingManager;
 use crate::core::thing::modifier::ModifierStorage;
-use crate::core::thing::resource::{Resource, ResourceAsset, ResourceStorage};
+use crate::core::thing::resource::{RateWindow, Resource, ResourceAsset, ResourceSave, ResourceSnapshot, ResourceStorage, ResourceUnlockRule};
+use crate::core::thing::upgrade::UpgradeManager;
 use std::collections::HashMap;
+use crate::core::number::{to_number, Number};
 
 /// Resource manager.
 pub struct ResourceManager {
@@ -31,7 +34,6 @@ impl ResourceManager{
         self.calculated_modifiers.clear();
         for (_, resource) in self.resources.iter_mut() {
 
-            resource.produce();
             resource.calculate();
             self.calculated_modifiers.combine(resource.calculated_modifiers())
             
@@ -39,31 +41,81 @@ impl ResourceManager{
         
     }
     
+    /// Produces resources using current production and consumption.
+    ///
+    /// Overflow of resources is converted into other resources after every resource is produced.
+    /// The rate history is recorded by `record_statistics`, once everything in the tick has changed the counts.
+    ///
+    /// # Params
+    ///
+    /// - `modifier_storage`: Modifiers that can be used for calculating decay.
+    pub fn produce(&mut self, modifier_storage: &ModifierStorage) {
+        
+        for (_, resource) in self.resources.iter_mut() {
+            
+            resource.produce(modifier_storage);
+            
+        }
+        
+        let conversions = self.resources
+            .values()
+            .filter_map(|r| r.asset().overflow.as_ref().map(|o| (o.name.clone(), r.wasted() * o.ratio)))
+            .filter(|(_, value)| *value > 0f64)
+            .collect::<Vec<_>>();
+        
+        for (name, value) in conversions {
+            
+            self.add_count(&name, value);
+            
+        }
+        
+    }
+    
+    /// Records changes of the tick in the rate history of every resource.
+    pub fn record_statistics(&mut self) {
+        
+        for (_, resource) in self.resources.iter_mut() {
+            
+            resource.record_statistics();
+            
+        }
+        
+    }
+    
     /// Sets production of resources.
+    ///
+    /// Resources missing from the storage have no production.
     pub fn set_production(&mut self, resource_storage: &ResourceStorage) {
         
-        for (name, value) in resource_storage.iter() {
+        for (name, resource) in self.resources.iter_mut() {
 
-            if let Some(resource) = self.resources.get_mut(name) {
-                
-                resource.set_production(*value);
-                
-            }
+            resource.set_production(resource_storage.value(name));
             
         }
         
     }
     
     /// Sets consumption of resources.
+    ///
+    /// Resources missing from the storage have no consumption.
     pub fn set_consumption(&mut self, resource_storage: &ResourceStorage) {
         
-        for (name, value) in resource_storage.iter() {
+        for (name, resource) in self.resources.iter_mut() {
 
-            if let Some(resource) = self.resources.get_mut(name) {
+            resource.set_consumption(resource_storage.value(name));
 
-                resource.set_consumption(*value);
+        }
+        
+    }
+    
+    /// Sets additional capacity of resources.
+    ///
+    /// Resources missing from the storage only have their base capacity.
+    pub fn set_capacity(&mut self, resource_storage: &ResourceStorage) {
+        
+        for (name, resource) in self.resources.iter_mut() {
 
-            }
+            resource.set_capacity(resource_storage.value(name));
 
         }
         
@@ -86,16 +138,173 @@ impl ResourceManager{
         
     }
     
+    /// Returns true if the resource would be drained with the additional consumption per tick.
+    pub fn is_drained_with(&self, name: &str, demand: Number) -> bool {
+        
+        self.resources
+            .get(name)
+            .map(|r| r.is_drained_with(demand))
+            .unwrap_or(true)
+        
+    }
+    
+    /// Is the resource unlocked?
+    pub fn is_unlocked(&self, name: &str) -> bool {
+        
+        self.resources
+            .get(name)
+            .map(|r| r.is_unlocked())
+            .unwrap_or(false)
+        
+    }
+    
+    /// Returns true if the flow resource is demanded but not supplied at all.
+    pub fn is_unsupplied(&self, name: &str) -> bool {
+        
+        self.resources
+            .get(name)
+            .map(|r| r.is_unsupplied())
+            .unwrap_or(false)
+        
+    }
+    
+    /// Returns true if the flow resource would be demanded with the additional consumption per tick but is not supplied at all.
+    pub fn is_unsupplied_with(&self, name: &str, demand: Number) -> bool {
+        
+        self.resources
+            .get(name)
+            .map(|r| r.is_unsupplied_with(demand))
+            .unwrap_or(false)
+        
+    }
+    
+    /// Adds to the resource's count.
+    pub fn add_count(&mut self, name: &str, count: Number) {
+        
+        if let Some(resource) = self.resources.get_mut(name) { resource.add_count(count); }
+        
+    }
+    
+    /// Is the resource a flow resource?
+    pub fn is_flow(&self, name: &str) -> bool {
+        
+        self.resources
+            .get(name)
+            .map(|r| r.is_flow())
+            .unwrap_or(false)
+        
+    }
+    
     /// Returns count of the resource.
-    pub fn count(&self, name: &str) -> f64 {
+    pub fn count(&self, name: &str) -> Number {
         
         self.resources
             .get(name)
             .map(|r| r.count())
+            .unwrap_or(to_number(0f64))
+        
+    }
+    
+    /// Returns capacity of the resource.
+    pub fn capacity(&self, name: &str) -> Number {
+        
+        self.resources
+            .get(name)
+            .map(|r| r.capacity())
+            .unwrap_or(to_number(0f64))
+        
+    }
+    
+    /// Returns average net change of the resource per tick in the window.
+    pub fn average_rate(&self, name: &str, window: RateWindow) -> f64 {
+        
+        self.resources
+            .get(name)
+            .map(|r| r.average_rate(window))
             .unwrap_or(0f64)
         
     }
     
+    /// Returns estimated ticks until the resource reaches its capacity.
+    pub fn ticks_until_capacity(&self, name: &str, window: RateWindow) -> Option<f64> {
+        
+        self.resources
+            .get(name)
+            .and_then(|r| r.ticks_until_capacity(window))
+        
+    }
+    
+    /// Returns estimated ticks until the resource runs empty.
+    pub fn ticks_until_empty(&self, name: &str, window: RateWindow) -> Option<f64> {
+        
+        self.resources
+            .get(name)
+            .and_then(|r| r.ticks_until_empty(window))
+        
+    }
+    
+}
+
+/// Implementations related to unlocking resources.
+impl ResourceManager {
+
+    /// Unlocks resources whose unlock rules are met.
+    ///
+    /// Returns names of the newly unlocked resources.
+    pub fn update_unlocks(&mut self, building_manager: &BuildingManager, upgrade_manager: &UpgradeManager) -> Vec<String> {
+
+        let mut unlocked = Vec::new();
+
+        for (name, resource) in self.resources.iter_mut() {
+
+            if resource.is_unlocked() { continue; }
+
+            let is_met = resource.asset()
+                .unlock_rules
+                .iter()
+                .any(|rule| match rule {
+                    ResourceUnlockRule::Count => resource.count() > 0f64,
+                    ResourceUnlockRule::Building => building_manager
+                        .iter()
+                        .any(|(_, b)| b.is_unlocked() && b.supplies_resource(name)),
+                    ResourceUnlockRule::Upgrade { name } => upgrade_manager.count(name) > 0,
+                });
+
+            if is_met {
+
+                resource.unlock(true);
+                unlocked.push(name.clone());
+
+            }
+
+        }
+
+        unlocked.sort();
+        unlocked
+
+    }
+
+}
+
+/// Implementations related to snapshots.
+impl ResourceManager {
+
+    /// Creates snapshots of unlocked resources, sorted by name.
+    ///
+    /// Locked resources are hidden.
+    pub fn snapshot(&self) -> Vec<ResourceSnapshot> {
+
+        let mut snapshots = self.resources
+            .values()
+            .filter(|r| r.is_unlocked())
+            .map(ResourceSnapshot::from)
+            .collect::<Vec<_>>();
+
+        snapshots.sort_by(|a, b| a.name.cmp(&b.name));
+        snapshots
+
+    }
+
 }
 
 /// Implementations related to loading and registering resources.
@@ -130,3 +339,125 @@ impl ResourceManager {
     }
 
 }
+
+/// Implementations related to saves.
+impl ResourceManager {
+
+    /// Creates saves of resources, sorted by name.
+    pub fn save(&self) -> Vec<ResourceSave> {
+
+        let mut saves = self.resources
+            .values()
+            .map(ResourceSave::from)
+            .collect::<Vec<_>>();
+
+        saves.sort_by(|a, b| a.name.cmp(&b.name));
+        saves
+
+    }
+
+    /// Restores resources from saves.
+    ///
+    /// Saves of resources that do not exist are ignored.
+    pub fn load_save(&mut self, saves: &[ResourceSave]) {
+
+        for save in saves.iter() {
+
+            let Some(resource) = self.resources.get_mut(&save.name) else { continue };
+
+            resource.set_count(save.count.max(0f64));
+            resource.unlock(save.is_unlocked);
+
+        }
+
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::ResourceManager;
+    use crate::core::number::{to_f64, to_number};
+    use crate::core::thing::modifier::ModifierStorage;
+    use crate::core::thing::resource::ResourceStorage;
+
+    /// Creates wood with 6 of 10 stored that overflows into planks at half ratio, and planks with the capacity.
+    fn manager(planks_capacity: f64) -> ResourceManager {
+
+        let mut manager = ResourceManager::new();
+        manager.load_from_str(r#"{"name":"wood","category":"raw","base_capacity":10,"modifiers":[],"overflow":{"name":"planks","ratio":0.5}}"#).unwrap();
+        manager.load_from_str(&format!(r#"{{"name":"planks","category":"crafted","base_capacity":{planks_capacity},"modifiers":[]}}"#)).unwrap();
+        manager.add_count("wood", to_number(6f64));
+
+        manager
+
+    }
+
+    fn produce(manager: &mut ResourceManager, wood: f64) {
+
+        let mut production = ResourceStorage::new();
+        production.add("wood".to_string(), to_number(wood));
+
+        manager.set_production(&production);
+        manager.produce(&ModifierStorage::new());
+
+    }
+
+    fn wasted(manager: &ResourceManager, name: &str) -> f64 {
+
+        to_f64(manager.resources[name].wasted())
+
+    }
+
+    fn assert_close(value: f64, expected: f64) {
+
+        assert!((value - expected).abs() < 1e-9, "{value} != {expected}");
+
+    }
+
+    #[test]
+    fn production_above_capacity_is_wasted_for_the_tick() {
+
+        let mut manager = manager(100f64);
+
+        produce(&mut manager, 8f64);
+        assert_close(to_f64(manager.count("wood")), 10f64);
+        assert_close(wasted(&manager, "wood"), 4f64);
+
+        produce(&mut manager, 0f64);
+        assert_close(wasted(&manager, "wood"), 0f64);
+
+    }
+
+    #[test]
+    fn wasted_resource_is_converted_by_the_ratio() {
+
+        let mut manager = manager(100f64);
+
+        produce(&mut manager, 8f64);
+        assert_close(to_f64(manager.count("planks")), 2f64);
+
+        produce(&mut manager, 2f64);
+        assert_close(to_f64(manager.count("planks")), 3f64);
+
+    }
+
+    #[test]
+    fn conversion_into_a_full_resource_is_wasted_there() {
+
+        let mut manager = manager(1f64);
+        manager.add_count("planks", to_number(1f64));
+
+        produce(&mut manager, 8f64);
+        assert_close(to_f64(manager.count("planks")), 1f64);
+        assert_close(wasted(&manager, "planks"), 0f64);
+
+        // The conversion arrives after planks produced, so it is wasted in the next tick.
+        produce(&mut manager, 0f64);
+        assert_close(wasted(&manager, "planks"), 2f64);
+        assert_close(to_f64(manager.count("planks")), 1f64);
+
+    }
+
+}
@@ -1,9 +1,15 @@
 mod asset;
 mod data;
 mod manager;
+mod save;
+mod snapshot;
+mod statistics;
 mod storage;
 
-pub use asset::ResourceAsset;
-pub use data::Resource;
+pub use asset::{ResourceAsset, ResourceKind, ResourceUnlockRule};
+pub use data::{flow_utilization, Resource};
 pub use manager::ResourceManager;
+pub use save::ResourceSave;
+pub use snapshot::ResourceSnapshot;
+pub use statistics::RateWindow;
 pub use storage::ResourceStorage;
@@ -0,0 +1,31 @@
+use crate::core::number::Number;
+use crate::core::thing::resource::Resource;
+use serde::{Deserialize, Serialize};
+
+/// Saved state of a resource.
+#[derive(Serialize, Deserialize)]
+pub struct ResourceSave {
+
+    /// Name of the resource.
+    pub name: String,
+    /// Resource count.
+    pub count: Number,
+    /// Is the resource unlocked?
+    #[serde(default)]
+    pub is_unlocked: bool,
+
+}
+
+impl From<&Resource> for ResourceSave {
+
+    fn from(resource: &Resource) -> Self {
+
+        Self {
+            name: resource.asset().name.clone(),
+            count: resource.count(),
+            is_unlocked: resource.is_unlocked(),
+        }
+
+    }
+
+}
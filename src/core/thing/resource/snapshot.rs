@@ -0,0 +1,83 @@
+use crate::core::thing::resource::{RateWindow, Resource, ResourceKind};
+use serde::Serialize;
+use crate::core::number::Number;
+
+/// Snapshot of a resource.
+#[derive(Serialize)]
+pub struct ResourceSnapshot {
+
+    /// Name of the resource.
+    pub name: String,
+    /// Category of the resource.
+    pub category: String,
+    /// Kind of the resource.
+    pub kind: ResourceKind,
+
+    /// Resource count.
+    pub count: Number,
+    /// Capacity of the resource.
+    pub capacity: Number,
+    /// Resource production per tick.
+    pub production: Number,
+    /// Resource consumption per tick.
+    pub consumption: Number,
+    /// Resource lost to decay in the last tick.
+    pub decay: Number,
+    /// Resource above the capacity in the last tick.
+    pub wasted: Number,
+
+    /// Supply per tick of a flow resource.
+    pub supply: Option<Number>,
+    /// Demand per tick of a flow resource.
+    pub demand: Option<Number>,
+    /// Ratio of the demand that the supply of a flow resource covers.
+    pub utilization: Option<f64>,
+
+    /// Average net change per tick in the last 10 ticks.
+    pub rate_short: f64,
+    /// Average net change per tick in the last minute.
+    pub rate_minute: f64,
+    /// Average net change per tick in the last hour.
+    pub rate_hour: f64,
+    /// Average amount lost to decay per tick in the last minute.
+    pub decay_rate_minute: f64,
+    /// Average amount above the capacity per tick in the last minute.
+    pub wasted_rate_minute: f64,
+    /// Estimated ticks until the resource reaches its capacity.
+    pub ticks_until_capacity: Option<f64>,
+    /// Estimated ticks until the resource runs empty.
+    pub ticks_until_empty: Option<f64>,
+
+}
+
+impl From<&Resource> for ResourceSnapshot {
+
+    fn from(resource: &Resource) -> Self {
+
+        let is_flow = resource.is_flow();
+
+        Self {
+            name: resource.asset().name.clone(),
+            category: resource.asset().category.clone(),
+            kind: resource.asset().kind,
+            count: resource.count(),
+            capacity: resource.capacity(),
+            production: resource.production(),
+            consumption: resource.consumption(),
+            decay: resource.decay(),
+            wasted: resource.wasted(),
+            supply: is_flow.then(|| resource.supply()),
+            demand: is_flow.then(|| resource.demand()),
+            utilization: is_flow.then(|| resource.utilization()),
+            rate_short: resource.average_rate(RateWindow::Short),
+            rate_minute: resource.average_rate(RateWindow::Minute),
+            rate_hour: resource.average_rate(RateWindow::Hour),
+            decay_rate_minute: resource.average_decay(RateWindow::Minute),
+            wasted_rate_minute: resource.average_wasted(RateWindow::Minute),
+            ticks_until_capacity: resource.ticks_until_capacity(RateWindow::Short),
+            ticks_until_empty: resource.ticks_until_empty(RateWindow::Short),
+        }
+
+    }
+
+}
@@ -0,0 +1,244 @@
+use crate::core::thing::TICKS_PER_SECOND;
+
+/// Window of ticks used for averaging rates.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum RateWindow {
+    /// Last 10 ticks.
+    Short,
+    /// Last minute.
+    Minute,
+    /// Last hour.
+    Hour,
+}
+
+impl RateWindow {
+
+    /// All windows, from the shortest to the longest.
+    pub const ALL: [RateWindow; 3] = [RateWindow::Short, RateWindow::Minute, RateWindow::Hour];
+
+    /// Number of ticks in the window.
+    pub fn ticks(&self) -> usize {
+
+        match self {
+            RateWindow::Short => 10,
+            RateWindow::Minute => 60 * TICKS_PER_SECOND,
+            RateWindow::Hour => 60 * 60 * TICKS_PER_SECOND,
+        }
+
+    }
+
+    fn index(&self) -> usize {
+
+        match self {
+            RateWindow::Short => 0,
+            RateWindow::Minute => 1,
+            RateWindow::Hour => 2,
+        }
+
+    }
+
+}
+
+/// Fixed-size history of resource changes per tick.
+pub struct ResourceStatistics {
+
+    /// Net change of the count.
+    net: RollingHistory,
+    /// Amount lost to decay.
+    decay: RollingHistory,
+    /// Amount above the capacity.
+    wasted: RollingHistory,
+
+}
+
+impl ResourceStatistics {
+
+    /// Creates an empty history.
+    pub fn new() -> Self {
+
+        Self {
+            net: RollingHistory::new(),
+            decay: RollingHistory::new(),
+            wasted: RollingHistory::new(),
+        }
+
+    }
+
+    /// Records changes of a tick.
+    ///
+    /// # Params
+    ///
+    /// - `change`: Net change of the count, including decay.
+    /// - `decay`: Amount lost to decay.
+    /// - `wasted`: Amount above the capacity.
+    pub fn record(&mut self, change: f64, decay: f64, wasted: f64) {
+
+        self.net.record(change);
+        self.decay.record(decay);
+        self.wasted.record(wasted);
+
+    }
+
+    /// Returns average net change per tick in the window.
+    pub fn average(&self, window: RateWindow) -> f64 {
+
+        self.net.average(window)
+
+    }
+
+    /// Returns average amount lost to decay per tick in the window.
+    pub fn average_decay(&self, window: RateWindow) -> f64 {
+
+        self.decay.average(window)
+
+    }
+
+    /// Returns average amount above the capacity per tick in the window.
+    pub fn average_wasted(&self, window: RateWindow) -> f64 {
+
+        self.wasted.average(window)
+
+    }
+
+}
+
+/// Ring buffer of values with running sums for each window.
+struct RollingHistory {
+
+    /// Recorded values, large enough for the longest window.
+    history: Vec<f64>,
+    /// Index the next value is written to.
+    next: usize,
+    /// Number of recorded values.
+    length: usize,
+    /// Sum of the values in each window.
+    sums: [f64; 3],
+
+}
+
+impl RollingHistory {
+
+    fn new() -> Self {
+
+        Self {
+            history: vec![0f64; RateWindow::Hour.ticks()],
+            next: 0,
+            length: 0,
+            sums: [0f64; 3],
+        }
+
+    }
+
+    fn record(&mut self, value: f64) {
+
+        let size = self.history.len();
+
+        for window in RateWindow::ALL.iter() {
+
+            let ticks = window.ticks();
+            if self.length >= ticks {
+
+                self.sums[window.index()] -= self.history[(self.next + size - ticks) % size];
+
+            }
+
+            self.sums[window.index()] += value;
+
+        }
+
+        self.history[self.next] = value;
+        self.next = (self.next + 1) % size;
+        self.length = (self.length + 1).min(size);
+
+        // Removes floating point errors accumulated by the running sums.
+        if self.next == 0 { self.recalculate_sums(); }
+
+    }
+
+    fn average(&self, window: RateWindow) -> f64 {
+
+        let ticks = window.ticks().min(self.length);
+        if ticks == 0 { return 0f64; }
+
+        self.sums[window.index()] / ticks as f64
+
+    }
+
+    fn recalculate_sums(&mut self) {
+
+        let size = self.history.len();
+
+        for window in RateWindow::ALL.iter() {
+
+            let ticks = window.ticks().min(self.length);
+            self.sums[window.index()] = (1..=ticks)
+                .map(|offset| self.history[(self.next + size - offset) % size])
+                .sum();
+
+        }
+
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{RateWindow, RollingHistory};
+
+    #[test]
+    fn empty_history_averages_to_zero() {
+
+        let history = RollingHistory::new();
+
+        for window in RateWindow::ALL.iter() {
+
+            assert_eq!(history.average(*window), 0f64);
+
+        }
+
+    }
+
+    #[test]
+    fn partial_window_averages_recorded_ticks() {
+
+        let mut history = RollingHistory::new();
+        history.record(2f64);
+        history.record(4f64);
+
+        assert_eq!(history.average(RateWindow::Short), 3f64);
+        assert_eq!(history.average(RateWindow::Hour), 3f64);
+
+    }
+
+    #[test]
+    fn old_values_leave_the_short_window() {
+
+        let mut history = RollingHistory::new();
+        (0..10).for_each(|_| history.record(10f64));
+        (0..10).for_each(|_| history.record(1f64));
+
+        assert_eq!(history.average(RateWindow::Short), 1f64);
+        assert_eq!(history.average(RateWindow::Minute), 5.5f64);
+
+    }
+
+    #[test]
+    fn windows_stay_correct_after_wrapping() {
+
+        let mut history = RollingHistory::new();
+        let size = RateWindow::Hour.ticks();
+
+        (0..size).for_each(|_| history.record(1f64));
+        (0..RateWindow::Minute.ticks()).for_each(|_| history.record(3f64));
+
+        assert_eq!(history.average(RateWindow::Short), 3f64);
+        assert_eq!(history.average(RateWindow::Minute), 3f64);
+
+        let minute = RateWindow::Minute.ticks() as f64;
+        let expected = (3f64 * minute + (size as f64 - minute)) / size as f64;
+        assert!((history.average(RateWindow::Hour) - expected).abs() < 1e-9);
+
+    }
+
+}
@@ -1,9 +1,11 @@
 use std::collections::hash_map::Iter;
 use std::collections::HashMap;
 
+use crate::core::number::{to_number, Number};
+
 /// Simple key-value storage for storing resource values.
 pub struct ResourceStorage {
-    resources: HashMap<String, f64>,
+    resources: HashMap<String, Number>,
 }
 
 impl ResourceStorage {
@@ -25,16 +27,23 @@ impl ResourceStorage {
     }
     
     /// Iterates through all entries.
-    pub fn iter(&self) -> Iter<String, f64> {
+    pub fn iter(&self) -> Iter<String, Number> {
         
         self.resources.iter()
         
     }
     
+    /// Returns value of an entry.
+    pub fn value(&self, name: &str) -> Number {
+        
+        self.resources.get(name).copied().unwrap_or(to_number(0f64))
+        
+    }
+    
     /// Adds value to an entry.
-    pub fn add(&mut self, name: String, value: f64) {
+    pub fn add(&mut self, name: String, value: Number) {
         
-        *self.resources.entry(name).or_insert(0f64) += value;
+        *self.resources.entry(name).or_insert(to_number(0f64)) += value;
         
     }
     
@@ -45,4 +54,11 @@ impl ResourceStorage {
         
     }
     
+    /// Combines values from other resource storage multiplied by the ratio.
+    pub fn combine_scaled(&mut self, other: &ResourceStorage, ratio: f64) {
+        
+        other.iter().for_each(|v| { self.add(v.0.clone(), *v.1 * ratio) })
+        
+    }
+    
 }
\ No newline at end of file
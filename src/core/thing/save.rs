@@ -0,0 +1,43 @@
+use crate::core::number::Number;
+use crate::core::thing::achievement::AchievementSave;
+use crate::core::thing::building::{BuildingConstruction, BuildingSave};
+use crate::core::thing::gather::GatherSave;
+use crate::core::thing::prestige::PrestigeSave;
+use crate::core::thing::resource::ResourceSave;
+use crate::core::thing::upgrade::UpgradeSave;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Saved state of things.
+///
+/// Only the player's progress is saved, everything else is calculated from the assets.
+/// Things that are missing from the save keep their initial state.
+#[derive(Serialize, Deserialize, Default)]
+pub struct ThingSave {
+
+    /// Resources.
+    #[serde(default)]
+    pub resources: Vec<ResourceSave>,
+    /// Buildings.
+    #[serde(default)]
+    pub buildings: Vec<BuildingSave>,
+    /// Buildings waiting to be constructed, in the order they were purchased.
+    #[serde(default)]
+    pub construction: Vec<BuildingConstruction>,
+    /// Upgrades.
+    #[serde(default)]
+    pub upgrades: Vec<UpgradeSave>,
+    /// Gather actions.
+    #[serde(default)]
+    pub gathers: Vec<GatherSave>,
+    /// Achievements.
+    #[serde(default)]
+    pub achievements: Vec<AchievementSave>,
+    /// Lifetime statistics, sorted by name.
+    #[serde(default)]
+    pub statistics: BTreeMap<String, Number>,
+    /// Prestige currency and prestige upgrades.
+    #[serde(default)]
+    pub prestige: PrestigeSave,
+
+}
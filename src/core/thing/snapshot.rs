@@ -0,0 +1,69 @@
+use crate::core::thing::achievement::AchievementSnapshot;
+use crate::core::thing::building::{BuildingConstructionQueueSnapshot, BuildingSnapshot};
+use crate::core::thing::gather::GatherSnapshot;
+use crate::core::thing::land::LandSnapshot;
+use crate::core::thing::population::PopulationSnapshot;
+use crate::core::thing::prestige::PrestigeSnapshot;
+use crate::core::thing::recipe::RecipeSnapshot;
+use crate::core::thing::resource::{ResourceSnapshot, ResourceStorage};
+use crate::core::thing::upgrade::UpgradeSnapshot;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use crate::core::number::Number;
+
+/// Snapshot of things for the UI.
+#[derive(Serialize)]
+pub struct ThingSnapshot {
+
+    /// Unlocked resources.
+    pub resources: Vec<ResourceSnapshot>,
+    /// Buildings.
+    pub buildings: Vec<BuildingSnapshot>,
+    /// Buildings waiting to be constructed.
+    pub construction: BuildingConstructionQueueSnapshot,
+    /// Upgrades.
+    pub upgrades: Vec<UpgradeSnapshot>,
+    /// Recipes.
+    pub recipes: Vec<RecipeSnapshot>,
+    /// Gather actions.
+    pub gathers: Vec<GatherSnapshot>,
+    /// Land used by buildings.
+    pub land: LandSnapshot,
+    /// Population, `None` if there is no population.
+    pub population: Option<PopulationSnapshot>,
+    /// Achievements.
+    pub achievements: Vec<AchievementSnapshot>,
+    /// Lifetime statistics, sorted by name.
+    pub statistics: BTreeMap<String, Number>,
+    /// Prestige, `None` if there is no prestige.
+    pub prestige: Option<PrestigeSnapshot>,
+
+}
+
+/// Snapshot of a single resource value, such as a price entry.
+#[derive(Serialize, Clone)]
+pub struct ResourceValueSnapshot {
+
+    /// Resource name.
+    pub name: String,
+    /// Resource value.
+    pub value: Number,
+
+}
+
+impl ResourceValueSnapshot {
+
+    /// Creates snapshots of every entry in the storage, sorted by resource name.
+    pub fn from_storage(resource_storage: &ResourceStorage) -> Vec<Self> {
+
+        let mut values = resource_storage
+            .iter()
+            .map(|(name, value)| Self { name: name.clone(), value: *value })
+            .collect::<Vec<_>>();
+
+        values.sort_by(|a, b| a.name.cmp(&b.name));
+        values
+
+    }
+
+}
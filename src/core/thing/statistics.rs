@@ -0,0 +1,72 @@
+use crate::core::number::{to_number, Number};
+use crate::core::thing::event::ThingEvent;
+use std::collections::{BTreeMap, HashMap};
+
+/// Lifetime statistics of the player, kept as named counters.
+///
+/// - `ticks`: Ticks played.
+/// - `resource.<name>.produced`: Resource produced by buildings and gather actions.
+/// - `building.<name>.purchased`: Buildings purchased.
+/// - `upgrade.<name>.purchased`: Upgrade levels purchased.
+/// - `recipe.<name>.crafted`: Recipes crafted.
+/// - `event.<type>` and `event.<type>.<name>`: Events that happened, such as `event.gathered.berries`.
+pub struct LifetimeStatistics {
+
+    /// Values of the statistics.
+    values: HashMap<String, Number>,
+
+}
+
+impl LifetimeStatistics {
+
+    /// Creates empty statistics.
+    pub fn new() -> Self {
+
+        Self {
+            values: HashMap::new(),
+        }
+
+    }
+
+    /// Returns value of the statistic, 0 if nothing has been recorded yet.
+    pub fn value(&self, name: &str) -> Number {
+
+        self.values.get(name).copied().unwrap_or(to_number(0f64))
+
+    }
+
+    /// Adds to the statistic.
+    pub fn add(&mut self, name: String, value: Number) {
+
+        *self.values.entry(name).or_insert(to_number(0f64)) += value;
+
+    }
+
+    /// Records an event that happened.
+    pub fn record_event(&mut self, event: &ThingEvent) {
+
+        self.add(format!("event.{}", event.kind()), to_number(1f64));
+        self.add(format!("event.{}.{}", event.kind(), event.name()), to_number(1f64));
+
+    }
+
+}
+
+/// Implementations related to saves.
+impl LifetimeStatistics {
+
+    /// Creates a save of the statistics, sorted by name.
+    pub fn save(&self) -> BTreeMap<String, Number> {
+
+        self.values.iter().map(|(name, value)| (name.clone(), *value)).collect()
+
+    }
+
+    /// Restores the statistics from a save, replacing the current ones.
+    pub fn load_save(&mut self, save: &BTreeMap<String, Number>) {
+
+        self.values = save.iter().map(|(name, value)| (name.clone(), *value)).collect();
+
+    }
+
+}
@@ -1,3 +1,4 @@
+use crate::core::thing::modifier::ModifierScaling;
 use serde::Deserialize;
 
 #[derive(Deserialize)]
@@ -12,6 +13,15 @@ pub struct UpgradeAsset {
     pub price: Vec<UpgradePrice>,
     /// Multiplier of the upgrade price.
     pub price_multiplier: f64,
+    /// Maximum level of the upgrade, `None` if it can be purchased infinitely.
+    ///
+    /// Upgrades are one-shot unless the asset says otherwise.
+    #[serde(default = "default_max_level")]
+    pub max_level: Option<i32>,
+
+    /// Upgrades that must be owned before the upgrade is available.
+    #[serde(default)]
+    pub prerequisites: Vec<String>,
     
     /// Things that the upgrade unlocks.
     pub unlocks: Vec<UpgradeUnlock>,
@@ -20,6 +30,12 @@ pub struct UpgradeAsset {
     
 }
 
+fn default_max_level() -> Option<i32> {
+
+    Some(1)
+
+}
+
 #[derive(Deserialize)]
 pub struct UpgradePrice {
 
@@ -49,5 +65,8 @@ pub struct UpgradeModifier {
     pub value: f64,
     /// Calculation method of the modifier.
     pub calculation: String,
+    /// Curve that scales the value by the upgrade level.
+    #[serde(default)]
+    pub scaling: ModifierScaling,
 
 }
@@ -1,6 +1,8 @@
-use crate::core::thing::modifier::ModifierStorage;
+use crate::core::thing::modifier::{ModifierCalculationMethod, ModifierEntry, ModifierStorage};
 use crate::core::thing::resource::ResourceStorage;
 use crate::core::thing::upgrade::asset::UpgradeAsset;
+use crate::core::number::to_number;
+use serde::Serialize;
 
 /// Upgrade
 pub struct Upgrade {
@@ -8,7 +10,7 @@ pub struct Upgrade {
     /// Upgrade's asset.
     asset: UpgradeAsset,
 
-    /// Upgrade count.
+    /// Upgrade level.
     count: i32,
 
     /// Calculated upgrade's modifiers.
@@ -59,15 +61,63 @@ impl Upgrade {
         self.is_unlocked = true;
 
     }
+
+    /// Returns level of the upgrade.
+    pub fn count(&self) -> i32 {
+
+        self.count
+
+    }
+
+    /// Sets level of the upgrade, limited by the maximum level.
+    pub fn set_count(&mut self, count: i32) {
+
+        self.count = match self.max_level() {
+            Some(max_level) => count.clamp(0, max_level),
+            None => count.max(0),
+        };
+
+    }
+
+    /// Returns maximum level of the upgrade, `None` if it can be purchased infinitely.
+    pub fn max_level(&self) -> Option<i32> {
+
+        self.asset.max_level.map(|max_level| max_level.max(0))
+
+    }
+
+    /// Has the upgrade reached its maximum level?
+    pub fn is_max_level(&self) -> bool {
+
+        self.max_level().is_some_and(|max_level| self.count >= max_level)
+
+    }
     
 }
 
 impl Upgrade {
     
     /// Calculate upgrade's modifiers and price.
+    ///
+    /// Modifiers scale with the level, and there is no price once the maximum level is reached.
     pub fn calculate(&mut self) {
         
-        unimplemented!()
+        self.calculated_modifiers.clear();
+        self.calculated_prices.clear();
+
+        for modifier in self.asset.modifiers.iter() {
+
+            self.calculated_modifiers.add(ModifierEntry::new(modifier.name.clone(), to_number(modifier.value * modifier.scaling.evaluate(self.count as f64)), ModifierCalculationMethod::from_str(modifier.calculation.as_str())));
+
+        }
+
+        if self.is_max_level() { return; }
+
+        for price in self.asset.price.iter() {
+
+            self.calculated_prices.add(price.name.to_string(), (price.value * to_number(self.asset.price_multiplier.max(1f64)).powi(self.count)).max(1f64).floor());
+
+        }
         
     }
 
@@ -86,3 +136,15 @@ impl Upgrade {
     }
     
 }
+
+/// State of an upgrade in the research tree.
+#[derive(Serialize, Copy, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum UpgradeState {
+    /// Some prerequisites are not owned yet.
+    Locked,
+    /// All prerequisites are owned, but the upgrade is not.
+    Available,
+    /// The upgrade is owned.
+    Owned,
+}
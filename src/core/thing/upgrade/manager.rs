@@ -1,6 +1,9 @@
-use super::{Upgrade, UpgradeAsset};
+use super::{Upgrade, UpgradeAsset, UpgradeSave, UpgradeSnapshot, UpgradeState, UpgradeTreeNodeSnapshot, UpgradeTreeSnapshot};
+use crate::core::thing::graph::strongly_connected_components;
 use crate::core::thing::modifier::ModifierStorage;
+use crate::core::thing::resource::ResourceManager;
 use std::collections::HashMap;
+use thiserror::Error;
 
 pub struct UpgradeManager {
 
@@ -21,6 +24,23 @@ impl UpgradeManager {
 
     }
 
+    /// Returns a single upgrade.
+    pub fn get(&self, name: &str) -> Option<&Upgrade> {
+
+        self.upgrades.get(name)
+
+    }
+
+    /// Returns level of the upgrade.
+    pub fn count(&self, name: &str) -> i32 {
+
+        self.upgrades
+            .get(name)
+            .map(|u| u.count())
+            .unwrap_or(0)
+
+    }
+
 }
 
 impl UpgradeManager {
@@ -55,6 +75,167 @@ impl UpgradeManager {
 
 }
 
+#[derive(Error, Debug)]
+pub enum UpgradeTreeError {
+    #[error("prerequisite '{1}' of upgrade '{0}' does not exist")]
+    MissingPrerequisite(String, String),
+    #[error("upgrades are prerequisites of each other, '{}'", .0.join("', '"))]
+    Cycle(Vec<String>),
+}
+
+/// Implementations related to the research tree.
+impl UpgradeManager {
+
+    /// Validates prerequisites of loaded upgrades.
+    ///
+    /// Must be called once all upgrades are loaded. Upgrades with a prerequisite that does not exist,
+    /// and upgrades that are prerequisites of each other, are removed, so the remaining upgrades form a tree.
+    /// Upgrades depending on a removed upgrade are removed as well.
+    pub fn validate_tree(&mut self) -> Vec<UpgradeTreeError> {
+
+        let mut errors = self.remove_missing_prerequisites();
+
+        let cycles = self.prerequisite_order()
+            .into_iter()
+            .filter(|group| self.is_cycle(group))
+            .collect::<Vec<_>>();
+
+        for cycle in cycles {
+
+            cycle.iter().for_each(|name| { self.upgrades.remove(name); });
+            errors.push(UpgradeTreeError::Cycle(cycle));
+
+        }
+
+        errors.extend(self.remove_missing_prerequisites());
+        errors
+
+    }
+
+    /// Removes upgrades with a prerequisite that does not exist, until every prerequisite exists.
+    fn remove_missing_prerequisites(&mut self) -> Vec<UpgradeTreeError> {
+
+        let mut errors = Vec::new();
+
+        loop {
+
+            let mut missing = self.upgrades
+                .values()
+                .flat_map(|u| {
+
+                    u.asset()
+                        .prerequisites
+                        .iter()
+                        .filter(|p| !self.upgrades.contains_key(*p))
+                        .map(|p| (u.asset().name.clone(), p.clone()))
+
+                })
+                .collect::<Vec<_>>();
+
+            if missing.is_empty() { return errors; }
+
+            missing.sort();
+            missing.iter().for_each(|(name, _)| { self.upgrades.remove(name); });
+            errors.extend(missing.into_iter().map(|(name, prerequisite)| UpgradeTreeError::MissingPrerequisite(name, prerequisite)));
+
+        }
+
+    }
+
+    /// Groups upgrade names so that every upgrade comes after its prerequisites.
+    fn prerequisite_order(&self) -> Vec<Vec<String>> {
+
+        let mut names = self.upgrades.keys().cloned().collect::<Vec<_>>();
+        names.sort();
+
+        let dependencies = self.upgrades
+            .iter()
+            .map(|(name, u)| (name.clone(), u.asset().prerequisites.clone()))
+            .collect();
+
+        strongly_connected_components(&names, &dependencies)
+
+    }
+
+    /// Is the group of upgrades a cycle, including an upgrade that is its own prerequisite?
+    fn is_cycle(&self, group: &[String]) -> bool {
+
+        group.len() > 1 || group.iter().any(|name| self.upgrades[name].asset().prerequisites.contains(name))
+
+    }
+
+    /// Returns state of the upgrade in the research tree, `None` if the upgrade does not exist.
+    pub fn state(&self, name: &str) -> Option<UpgradeState> {
+
+        let upgrade = self.upgrades.get(name)?;
+
+        if upgrade.count() > 0 { Some(UpgradeState::Owned) }
+        else if upgrade.asset().prerequisites.iter().all(|p| self.count(p) > 0) { Some(UpgradeState::Available) }
+        else { Some(UpgradeState::Locked) }
+
+    }
+
+    /// Creates layout of the research tree.
+    ///
+    /// Every upgrade is placed in the column after its deepest prerequisite, and upgrades in a column are sorted by name.
+    pub fn tree(&self) -> UpgradeTreeSnapshot {
+
+        let mut depths = HashMap::<String, usize>::new();
+
+        for name in self.prerequisite_order().into_iter().flatten() {
+
+            let depth = self.upgrades[&name].asset()
+                .prerequisites
+                .iter()
+                .filter_map(|p| depths.get(p))
+                .map(|depth| depth + 1)
+                .max()
+                .unwrap_or(0);
+
+            depths.insert(name, depth);
+
+        }
+
+        let mut nodes = self.upgrades
+            .values()
+            .map(|u| {
+
+                let name = &u.asset().name;
+                let mut prerequisites = u.asset().prerequisites.clone();
+                prerequisites.sort();
+
+                UpgradeTreeNodeSnapshot {
+                    name: name.clone(),
+                    category: u.asset().category.clone(),
+                    prerequisites,
+                    state: self.state(name).unwrap_or(UpgradeState::Locked),
+                    depth: depths.get(name).copied().unwrap_or(0),
+                    row: 0,
+                }
+
+            })
+            .collect::<Vec<_>>();
+
+        nodes.sort_by(|a, b| a.depth.cmp(&b.depth).then_with(|| a.name.cmp(&b.name)));
+
+        let mut rows = HashMap::<usize, usize>::new();
+        for node in nodes.iter_mut() {
+
+            let row = rows.entry(node.depth).or_insert(0);
+            node.row = *row;
+            *row += 1;
+
+        }
+
+        UpgradeTreeSnapshot {
+            depth: nodes.iter().map(|n| n.depth + 1).max().unwrap_or(0),
+            nodes,
+        }
+
+    }
+
+}
+
 impl UpgradeManager {
 
     /// Calculates upgrades.
@@ -79,3 +260,322 @@ impl UpgradeManager {
     }
 
 }
+
+#[derive(Error, Debug)]
+pub enum UpgradePurchaseError {
+    #[error("upgrade '{0}' does not exist")]
+    NotFound(String),
+    #[error("upgrade '{0}' is locked")]
+    Locked(String),
+    #[error("upgrade '{0}' is already at its maximum level {1}")]
+    MaxLevel(String, i32),
+    #[error("not enough resource '{0}'")]
+    NotEnoughResource(String),
+}
+
+/// Implementations related to purchasing upgrades.
+impl UpgradeManager {
+
+    /// Purchases the next level of an available or owned upgrade, and returns the new level.
+    ///
+    /// The price is taken from the resources.
+    pub fn purchase(&mut self, name: &str, resource_manager: &mut ResourceManager) -> Result<i32, UpgradePurchaseError> {
+
+        if self.state(name) == Some(UpgradeState::Locked) {
+
+            return Err(UpgradePurchaseError::Locked(name.to_string()));
+
+        }
+
+        let upgrade = self.upgrades
+            .get_mut(name)
+            .ok_or_else(|| UpgradePurchaseError::NotFound(name.to_string()))?;
+
+        if let Some(max_level) = upgrade.max_level().filter(|_| upgrade.is_max_level()) {
+
+            return Err(UpgradePurchaseError::MaxLevel(name.to_string(), max_level));
+
+        }
+
+        upgrade.calculate();
+
+        if let Some((resource, _)) = upgrade.calculated_prices()
+            .iter()
+            .find(|(resource, value)| resource_manager.count(resource) < **value) {
+
+            return Err(UpgradePurchaseError::NotEnoughResource(resource.clone()));
+
+        }
+
+        for (resource, value) in upgrade.calculated_prices().iter() {
+
+            resource_manager.add_count(resource, -*value);
+
+        }
+
+        upgrade.set_count(upgrade.count() + 1);
+        upgrade.calculate();
+
+        Ok(upgrade.count())
+
+    }
+
+}
+
+/// Implementations related to snapshots.
+impl UpgradeManager {
+
+    /// Creates snapshots of upgrades, sorted by name.
+    pub fn snapshot(&self) -> Vec<UpgradeSnapshot> {
+
+        let mut snapshots = self.upgrades
+            .values()
+            .map(|u| {
+
+                let mut snapshot = UpgradeSnapshot::from(u);
+                snapshot.state = self.state(&snapshot.name).unwrap_or(UpgradeState::Locked);
+                snapshot
+
+            })
+            .collect::<Vec<_>>();
+
+        snapshots.sort_by(|a, b| a.name.cmp(&b.name));
+        snapshots
+
+    }
+
+}
+
+/// Implementations related to saves.
+impl UpgradeManager {
+
+    /// Creates saves of upgrades, sorted by name.
+    pub fn save(&self) -> Vec<UpgradeSave> {
+
+        let mut saves = self.upgrades
+            .values()
+            .map(UpgradeSave::from)
+            .collect::<Vec<_>>();
+
+        saves.sort_by(|a, b| a.name.cmp(&b.name));
+        saves
+
+    }
+
+    /// Restores upgrades from saves.
+    ///
+    /// Saves of upgrades that do not exist are ignored.
+    pub fn load_save(&mut self, saves: &[UpgradeSave]) {
+
+        for save in saves.iter() {
+
+            let Some(upgrade) = self.upgrades.get_mut(&save.name) else { continue };
+
+            upgrade.set_count(save.count);
+            if save.is_unlocked { upgrade.unlock(); }
+
+        }
+
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{UpgradeManager, UpgradePurchaseError};
+    use crate::core::number::{to_f64, to_number};
+    use crate::core::thing::modifier::ModifierCalculationMethod;
+    use crate::core::thing::resource::ResourceManager;
+    use crate::core::thing::upgrade::UpgradeState;
+
+    /// Creates a JSON upgrade asset without a price that requires the prerequisites.
+    fn upgrade(name: &str, prerequisites: &[&str]) -> String {
+
+        let prerequisites = prerequisites
+            .iter()
+            .map(|p| format!(r#""{p}""#))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        format!(r#"{{"name":"{name}","category":"research","price":[],"price_multiplier":1,"prerequisites":[{prerequisites}],"unlocks":[],"modifiers":[]}}"#)
+
+    }
+
+    fn manager(upgrades: &[String]) -> UpgradeManager {
+
+        let mut manager = UpgradeManager::new();
+
+        for asset in upgrades.iter() {
+
+            manager.load_from_str(asset).unwrap();
+
+        }
+
+        manager
+
+    }
+
+    fn errors(manager: &mut UpgradeManager) -> Vec<String> {
+
+        manager.validate_tree()
+            .iter()
+            .map(|error| error.to_string())
+            .collect()
+
+    }
+
+    #[test]
+    fn missing_prerequisites_remove_their_dependents() {
+
+        let mut manager = manager(&[upgrade("a", &["ghost"]), upgrade("b", &["a"]), upgrade("c", &[])]);
+
+        assert_eq!(errors(&mut manager), vec![
+            "prerequisite 'ghost' of upgrade 'a' does not exist",
+            "prerequisite 'a' of upgrade 'b' does not exist",
+        ]);
+        assert!(manager.get("a").is_none());
+        assert!(manager.get("b").is_none());
+        assert!(manager.get("c").is_some());
+
+    }
+
+    #[test]
+    fn cycles_are_removed_with_their_dependents() {
+
+        let mut manager = manager(&[
+            upgrade("x", &["y"]),
+            upgrade("y", &["x"]),
+            upgrade("s", &["s"]),
+            upgrade("z", &["x"]),
+            upgrade("root", &[]),
+        ]);
+
+        assert_eq!(errors(&mut manager), vec![
+            "upgrades are prerequisites of each other, 's'",
+            "upgrades are prerequisites of each other, 'x', 'y'",
+            "prerequisite 'x' of upgrade 'z' does not exist",
+        ]);
+        assert_eq!(manager.tree().nodes.iter().map(|n| n.name.as_str()).collect::<Vec<_>>(), vec!["root"]);
+
+    }
+
+    #[test]
+    fn upgrades_become_available_when_all_prerequisites_are_owned() {
+
+        let mut manager = manager(&[upgrade("a", &[]), upgrade("b", &[]), upgrade("c", &["a", "b"])]);
+        let mut resource_manager = ResourceManager::new();
+        assert!(errors(&mut manager).is_empty());
+
+        assert_eq!(manager.state("c"), Some(UpgradeState::Locked));
+        assert!(matches!(manager.purchase("c", &mut resource_manager), Err(UpgradePurchaseError::Locked(_))));
+
+        manager.purchase("a", &mut resource_manager).unwrap();
+        assert_eq!(manager.state("a"), Some(UpgradeState::Owned));
+        assert_eq!(manager.state("c"), Some(UpgradeState::Locked));
+
+        manager.purchase("b", &mut resource_manager).unwrap();
+        assert_eq!(manager.state("c"), Some(UpgradeState::Available));
+        assert_eq!(manager.state("missing"), None);
+
+    }
+
+    #[test]
+    fn tree_places_upgrades_after_their_deepest_prerequisite() {
+
+        let manager = manager(&[
+            upgrade("a", &[]),
+            upgrade("b", &[]),
+            upgrade("c", &["a"]),
+            upgrade("d", &["a", "c"]),
+        ]);
+
+        let tree = manager.tree();
+        let layout = tree.nodes
+            .iter()
+            .map(|n| (n.name.as_str(), n.depth, n.row))
+            .collect::<Vec<_>>();
+
+        assert_eq!(tree.depth, 3);
+        assert_eq!(layout, vec![("a", 0, 0), ("b", 0, 1), ("c", 1, 0), ("d", 2, 0)]);
+        assert_eq!(tree.nodes[3].prerequisites, vec!["a".to_string(), "c".to_string()]);
+
+    }
+
+    /// Creates a manager with a `boost` upgrade that costs 10 wood and has the level settings, and resources with 1000 wood.
+    fn leveled(max_level: &str, scaling: &str) -> (UpgradeManager, ResourceManager) {
+
+        let manager = manager(&[format!(r#"{{"name":"boost","category":"research","price":[{{"name":"wood","value":10}}],"price_multiplier":2,{max_level}"unlocks":[],"modifiers":[{{"name":"resource.name.wood.production","value":0.5,"calculation":"additive"{scaling}}}]}}"#)]);
+
+        let mut resource_manager = ResourceManager::new();
+        resource_manager.load_from_str(r#"{"name":"wood","category":"raw","base_capacity":1000,"modifiers":[]}"#).unwrap();
+        resource_manager.add_count("wood", to_number(1000f64));
+
+        (manager, resource_manager)
+
+    }
+
+    fn modifier(manager: &mut UpgradeManager) -> f64 {
+
+        manager.calculate();
+        to_f64(manager.calculated_modifiers().value("resource.name.wood.production", ModifierCalculationMethod::Additive))
+
+    }
+
+    #[test]
+    fn upgrades_are_one_shot_by_default() {
+
+        let (mut manager, mut resource_manager) = leveled("", "");
+
+        assert_eq!(manager.purchase("boost", &mut resource_manager).unwrap(), 1);
+        assert!(matches!(manager.purchase("boost", &mut resource_manager), Err(UpgradePurchaseError::MaxLevel(_, 1))));
+        assert!(manager.get("boost").unwrap().calculated_prices().iter().next().is_none());
+        assert!((to_f64(resource_manager.count("wood")) - 990f64).abs() < 1e-9);
+
+    }
+
+    #[test]
+    fn repeatable_upgrades_scale_prices_and_modifiers_by_level() {
+
+        let (mut manager, mut resource_manager) = leveled(r#""max_level":null,"#, "");
+
+        for level in 1..=3 { assert_eq!(manager.purchase("boost", &mut resource_manager).unwrap(), level); }
+
+        // Levels cost 10, 20 and 40 wood.
+        assert!((to_f64(resource_manager.count("wood")) - 930f64).abs() < 1e-9);
+        assert!((modifier(&mut manager) - 1.5).abs() < 1e-9);
+
+    }
+
+    #[test]
+    fn modifiers_follow_the_scaling_curve() {
+
+        let (mut manager, mut resource_manager) = leveled(r#""max_level":10,"#, r#","scaling":{"type":"step","every":2}"#);
+
+        manager.purchase("boost", &mut resource_manager).unwrap();
+        assert_eq!(modifier(&mut manager), 0f64);
+
+        manager.purchase("boost", &mut resource_manager).unwrap();
+        assert!((modifier(&mut manager) - 0.5).abs() < 1e-9);
+
+    }
+
+    #[test]
+    fn saves_are_limited_to_the_max_level() {
+
+        let (mut manager, mut resource_manager) = leveled(r#""max_level":3,"#, "");
+        manager.purchase("boost", &mut resource_manager).unwrap();
+        manager.purchase("boost", &mut resource_manager).unwrap();
+
+        let mut saves = manager.save();
+        assert_eq!(saves[0].count, 2);
+
+        saves[0].count = 7;
+        manager.load_save(&saves);
+
+        assert_eq!(manager.count("boost"), 3);
+        assert!(manager.get("boost").unwrap().is_max_level());
+
+    }
+
+}
@@ -1,7 +1,11 @@
 mod asset;
 mod data;
 mod manager;
+mod save;
+mod snapshot;
 
 pub use asset::UpgradeAsset;
-pub use data::Upgrade;
-pub use manager::UpgradeManager;
+pub use data::{Upgrade, UpgradeState};
+pub use manager::{UpgradeManager, UpgradePurchaseError, UpgradeTreeError};
+pub use save::UpgradeSave;
+pub use snapshot::{UpgradeSnapshot, UpgradeTreeNodeSnapshot, UpgradeTreeSnapshot};
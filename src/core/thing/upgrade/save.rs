@@ -0,0 +1,30 @@
+use crate::core::thing::upgrade::Upgrade;
+use serde::{Deserialize, Serialize};
+
+/// Saved state of an upgrade.
+#[derive(Serialize, Deserialize)]
+pub struct UpgradeSave {
+
+    /// Name of the upgrade.
+    pub name: String,
+    /// Upgrade level.
+    pub count: i32,
+    /// Is the upgrade unlocked?
+    #[serde(default)]
+    pub is_unlocked: bool,
+
+}
+
+impl From<&Upgrade> for UpgradeSave {
+
+    fn from(upgrade: &Upgrade) -> Self {
+
+        Self {
+            name: upgrade.asset().name.clone(),
+            count: upgrade.count(),
+            is_unlocked: upgrade.is_unlocked(),
+        }
+
+    }
+
+}
@@ -0,0 +1,84 @@
+use crate::core::thing::snapshot::ResourceValueSnapshot;
+use crate::core::thing::upgrade::{Upgrade, UpgradeState};
+use serde::Serialize;
+
+/// Snapshot of an upgrade.
+#[derive(Serialize)]
+pub struct UpgradeSnapshot {
+
+    /// Name of the upgrade.
+    pub name: String,
+    /// Category of the upgrade.
+    pub category: String,
+
+    /// Upgrade level.
+    pub count: i32,
+    /// Maximum level of the upgrade, `None` if it can be purchased infinitely.
+    pub max_level: Option<i32>,
+    /// Has the upgrade reached its maximum level?
+    pub is_max_level: bool,
+    /// Price of the next level, empty once the maximum level is reached.
+    pub prices: Vec<ResourceValueSnapshot>,
+    /// Upgrades that must be owned before the upgrade is available, sorted by name.
+    pub prerequisites: Vec<String>,
+    /// State of the upgrade in the research tree.
+    pub state: UpgradeState,
+
+    /// Is the upgrade unlocked?
+    pub is_unlocked: bool,
+
+}
+
+impl From<&Upgrade> for UpgradeSnapshot {
+
+    fn from(upgrade: &Upgrade) -> Self {
+
+        let mut prerequisites = upgrade.asset().prerequisites.clone();
+        prerequisites.sort();
+
+        Self {
+            name: upgrade.asset().name.clone(),
+            category: upgrade.asset().category.clone(),
+            count: upgrade.count(),
+            max_level: upgrade.max_level(),
+            is_max_level: upgrade.is_max_level(),
+            prices: ResourceValueSnapshot::from_storage(upgrade.calculated_prices()),
+            prerequisites,
+            state: if upgrade.count() > 0 { UpgradeState::Owned } else { UpgradeState::Locked },
+            is_unlocked: upgrade.is_unlocked(),
+        }
+
+    }
+
+}
+
+/// Layout of the research tree.
+#[derive(Serialize)]
+pub struct UpgradeTreeSnapshot {
+
+    /// Count of columns in the tree.
+    pub depth: usize,
+    /// Upgrades, sorted by column and then by row.
+    pub nodes: Vec<UpgradeTreeNodeSnapshot>,
+
+}
+
+/// Upgrade placed in the research tree.
+#[derive(Serialize)]
+pub struct UpgradeTreeNodeSnapshot {
+
+    /// Name of the upgrade.
+    pub name: String,
+    /// Category of the upgrade.
+    pub category: String,
+    /// Upgrades that must be owned before the upgrade is available, sorted by name.
+    pub prerequisites: Vec<String>,
+    /// State of the upgrade.
+    pub state: UpgradeState,
+
+    /// Column of the upgrade, one after its deepest prerequisite.
+    pub depth: usize,
+    /// Row of the upgrade within its column.
+    pub row: usize,
+
+}
@@ -1,15 +1,22 @@
 use crate::assets::get_asset_list;
-use crate::core::{ThingManager, ThingManagerLoadError};
-use wasm_bindgen::prelude::wasm_bindgen;
+use crate::core::format::{format_duration, format_number, format_rate, NumberLocale, NumberNotation};
+use crate::core::number::{to_f64, to_number, Number};
+use crate::core::{ModifierScaling, ThingManager, ThingManagerLoadError, ThingSave, TICKS_PER_SECOND};
+use wasm_bindgen::prelude::{wasm_bindgen, JsValue};
 
 #[wasm_bindgen]
 pub struct Game {
     
     thing_manager: ThingManager,
     
+    locale: String,
+    number_locale: NumberLocale,
+    
     is_playing: bool,
     is_debug_mode: bool,
     
+    unresolved_modifier_cycles: Vec<Vec<String>>,
+    
 }
 
 // Constructor
@@ -28,8 +35,11 @@ impl Game {
 
         Self {
             thing_manager: ThingManager::new(),
+            locale: "en".to_string(),
+            number_locale: NumberLocale::from_locale("en"),
             is_playing: false,
             is_debug_mode: false,
+            unresolved_modifier_cycles: Vec::new(),
         }
 
     }
@@ -70,7 +80,16 @@ impl Game {
 
         if !self.is_playing { return }
         
-        
+        self.thing_manager.tick();
+
+        let unresolved = self.thing_manager.unresolved_modifier_cycles();
+        unresolved.iter()
+            .filter(|cycle| !self.unresolved_modifier_cycles.contains(cycle))
+            .for_each(|cycle| {
+                eprintln!("modifiers of buildings did not settle, '{}'", cycle.join("', '"))
+            });
+
+        self.unresolved_modifier_cycles = unresolved.to_vec();
 
     }
     
@@ -88,6 +107,134 @@ impl Game {
 
     }
 
+    /// Returns a JSON snapshot of things.
+    #[wasm_bindgen]
+    pub fn snapshot(&self) -> String {
+
+        serde_json::to_string(&self.thing_manager.snapshot()).unwrap()
+
+    }
+
+    /// Returns a JSON layout of the research tree.
+    #[wasm_bindgen]
+    pub fn upgrade_tree(&self) -> String {
+
+        serde_json::to_string(&self.thing_manager.upgrade_tree()).unwrap()
+
+    }
+
+    /// Returns a JSON affordability report of the building's next purchase, or `null` if it does not exist.
+    #[wasm_bindgen]
+    pub fn building_affordability(&self, name: &str) -> String {
+
+        serde_json::to_string(&self.thing_manager.building_affordability(name)).unwrap()
+
+    }
+
+    /// Returns a JSON affordability report of the upgrade's next purchase, or `null` if it does not exist.
+    #[wasm_bindgen]
+    pub fn upgrade_affordability(&self, name: &str) -> String {
+
+        serde_json::to_string(&self.thing_manager.upgrade_affordability(name)).unwrap()
+
+    }
+
+    /// Returns JSON array of events that happened since the last call.
+    #[wasm_bindgen]
+    pub fn poll_events(&mut self) -> String {
+
+        serde_json::to_string(&self.thing_manager.drain_events()).unwrap()
+
+    }
+
+}
+
+// Locale
+
+#[wasm_bindgen]
+impl Game {
+
+    /// Returns the active text locale.
+    #[wasm_bindgen]
+    pub fn locale(&self) -> String {
+
+        self.locale.clone()
+
+    }
+
+    /// Sets the active text locale. Number formatting follows it.
+    #[wasm_bindgen]
+    pub fn set_locale(&mut self, locale: &str) {
+
+        self.locale = locale.to_string();
+        self.number_locale = NumberLocale::from_locale(locale);
+
+    }
+
+}
+
+// Formatting
+
+#[wasm_bindgen]
+impl Game {
+
+    /// Formats a number from a snapshot, which is either a number or a `"<mantissa>e<exponent>"` string.
+    ///
+    /// The notation is one of `short`, `scientific`, `engineering` and `full`.
+    #[wasm_bindgen]
+    pub fn format_number(&self, value: JsValue, notation: &str) -> String {
+
+        format_number(Self::number_from_js(&value), Self::notation_from_str(notation), &self.number_locale)
+
+    }
+
+    /// Formats a rate per tick from a snapshot as a rate per second, such as `+1.2k/s`.
+    #[wasm_bindgen]
+    pub fn format_rate(&self, value_per_tick: JsValue, notation: &str) -> String {
+
+        format_rate(Self::number_from_js(&value_per_tick) * TICKS_PER_SECOND as f64, Self::notation_from_str(notation), &self.number_locale)
+
+    }
+
+    /// Formats ticks from a snapshot as a duration, such as `3h 12m`.
+    #[wasm_bindgen]
+    pub fn format_duration(ticks: f64) -> String {
+
+        format_duration(ticks / TICKS_PER_SECOND as f64)
+
+    }
+
+}
+
+impl Game {
+
+    fn number_from_js(value: &JsValue) -> Number {
+
+        if let Some(value) = value.as_f64() { return to_number(value); }
+
+        match value.as_string().map(|v| v.parse::<Number>()) {
+            Some(Ok(value)) => value,
+            _ => {
+
+                eprintln!("failed to format a number, '{value:?}' is not a number");
+                to_number(f64::NAN)
+
+            },
+        }
+
+    }
+
+    fn notation_from_str(notation: &str) -> NumberNotation {
+
+        NumberNotation::from_str_safe(notation).unwrap_or_else(|err| {
+
+            eprintln!("failed to format a number, {err}");
+            NumberNotation::default()
+
+        })
+
+    }
+
 }
 
 // Thing loading
@@ -124,7 +271,333 @@ impl Game {
             .for_each(|(thing_type, thing)| {
                 self.load_thing_from_string(thing_type, thing)
             });
-        
+
+        self.thing_manager
+            .modifier_cycles()
+            .iter()
+            .for_each(|cycle| {
+                eprintln!("buildings generate modifiers for each other or for themselves, '{}'", cycle.join("', '"))
+            });
+
+        self.thing_manager
+            .validate_upgrade_tree()
+            .iter()
+            .for_each(|err| {
+                eprintln!("failed to load an upgrade, {err}")
+            });
+
     }
     
 }
+
+// Saving
+
+#[wasm_bindgen]
+impl Game {
+
+    /// Returns a JSON save of the player's progress.
+    #[wasm_bindgen]
+    pub fn save(&self) -> String {
+
+        serde_json::to_string(&self.thing_manager.save()).unwrap()
+
+    }
+
+    /// Restores the player's progress from a JSON save. Returns false if the save could not be parsed.
+    #[wasm_bindgen]
+    pub fn load_save(&mut self, save: &str) -> bool {
+
+        match serde_json::from_str::<ThingSave>(save) {
+            Err(err) => {
+
+                eprintln!("failed to load save, failed to parse save from str, {err}");
+                false
+
+            },
+            Ok(save) => {
+
+                self.thing_manager.load_save(&save);
+                true
+
+            },
+        }
+
+    }
+
+}
+
+// Buildings
+
+#[wasm_bindgen]
+impl Game {
+
+    /// Purchases a building. Returns false if it could not be purchased.
+    #[wasm_bindgen]
+    pub fn purchase_building(&mut self, name: &str) -> bool {
+
+        match self.thing_manager.purchase_building(name) {
+            Err(err) => {
+
+                eprintln!("failed to purchase '{name}', {err}");
+                false
+
+            },
+            Ok(_) => true,
+        }
+
+    }
+
+    /// Sets how many of the buildings are active. Returns the active count after clamping to the building count.
+    #[wasm_bindgen]
+    pub fn set_building_active_count(&mut self, name: &str, active_count: i32) -> i32 {
+
+        match self.thing_manager.set_building_active_count(name, active_count) {
+            Err(err) => {
+
+                eprintln!("failed to set active count of '{name}', {err}");
+                0
+
+            },
+            Ok(active_count) => active_count,
+        }
+
+    }
+
+    /// Assigns population members to the building's jobs. Returns the workers after clamping to the free members and job slots.
+    #[wasm_bindgen]
+    pub fn assign_building_workers(&mut self, name: &str, workers: i32) -> i32 {
+
+        match self.thing_manager.assign_building_workers(name, workers) {
+            Err(err) => {
+
+                eprintln!("failed to assign workers to '{name}', {err}");
+                0
+
+            },
+            Ok(workers) => workers,
+        }
+
+    }
+
+    /// Activates or deactivates a production of the building. Returns false if it could not be changed.
+    #[wasm_bindgen]
+    pub fn set_building_production(&mut self, name: &str, production: &str, active: bool) -> bool {
+
+        match self.thing_manager.set_building_production(name, production, active) {
+            Err(err) => {
+
+                eprintln!("failed to change production '{production}' of '{name}', {err}");
+                false
+
+            },
+            Ok(_) => true,
+        }
+
+    }
+
+    /// Converts buildings into the next tier. Returns false if they could not be converted.
+    #[wasm_bindgen]
+    pub fn convert_building(&mut self, name: &str, count: i32) -> bool {
+
+        match self.thing_manager.convert_building(name, count) {
+            Err(err) => {
+
+                eprintln!("failed to convert '{name}', {err}");
+                false
+
+            },
+            Ok(_) => true,
+        }
+
+    }
+
+    /// Cancels a construction from the snapshot, refunding its price. Returns false if it could not be cancelled.
+    #[wasm_bindgen]
+    pub fn cancel_construction(&mut self, id: u32) -> bool {
+
+        match self.thing_manager.cancel_construction(id) {
+            Err(err) => {
+
+                eprintln!("failed to cancel construction, {err}");
+                false
+
+            },
+            Ok(_) => true,
+        }
+
+    }
+
+    /// Demolishes a building, refunding part of its price. Returns false if it could not be demolished.
+    #[wasm_bindgen]
+    pub fn demolish_building(&mut self, name: &str) -> bool {
+
+        match self.thing_manager.demolish_building(name) {
+            Err(err) => {
+
+                eprintln!("failed to demolish '{name}', {err}");
+                false
+
+            },
+            Ok(_) => true,
+        }
+
+    }
+
+}
+
+// Upgrades
+
+#[wasm_bindgen]
+impl Game {
+
+    /// Purchases the next level of an upgrade. Returns the new level, or 0 if it could not be purchased.
+    #[wasm_bindgen]
+    pub fn purchase_upgrade(&mut self, name: &str) -> i32 {
+
+        match self.thing_manager.purchase_upgrade(name) {
+            Err(err) => {
+
+                eprintln!("failed to purchase '{name}', {err}");
+                0
+
+            },
+            Ok(level) => level,
+        }
+
+    }
+
+}
+
+// Prestige
+
+#[wasm_bindgen]
+impl Game {
+
+    /// Returns prestige currency a reset would give right now.
+    #[wasm_bindgen]
+    pub fn prestige_preview(&self) -> f64 {
+
+        to_f64(self.thing_manager.prestige_preview())
+
+    }
+
+    /// Resets things for prestige currency. Returns the gained currency, or 0 if there was nothing to gain.
+    #[wasm_bindgen]
+    pub fn prestige(&mut self) -> f64 {
+
+        match self.thing_manager.prestige() {
+            Err(err) => {
+
+                eprintln!("failed to prestige, {err}");
+                0f64
+
+            },
+            Ok(gained) => to_f64(gained),
+        }
+
+    }
+
+    /// Purchases the next level of a prestige upgrade. Returns the new level, or 0 if it could not be purchased.
+    #[wasm_bindgen]
+    pub fn purchase_prestige_upgrade(&mut self, name: &str) -> i32 {
+
+        match self.thing_manager.purchase_prestige_upgrade(name) {
+            Err(err) => {
+
+                eprintln!("failed to purchase '{name}', {err}");
+                0
+
+            },
+            Ok(level) => level,
+        }
+
+    }
+
+}
+
+// Gathering
+
+#[wasm_bindgen]
+impl Game {
+
+    /// Triggers the gather action. Returns false if it could not be triggered.
+    #[wasm_bindgen]
+    pub fn gather(&mut self, name: &str) -> bool {
+
+        match self.thing_manager.gather(name) {
+            Err(err) => {
+
+                eprintln!("failed to gather '{name}', {err}");
+                false
+
+            },
+            Ok(_) => true,
+        }
+
+    }
+
+}
+
+// Crafting
+
+#[wasm_bindgen]
+impl Game {
+
+    /// Crafts the recipe and returns the crafted count.
+    #[wasm_bindgen]
+    pub fn craft(&mut self, recipe: &str, count: u32) -> u32 {
+
+        match self.thing_manager.craft(recipe, count) {
+            Err(err) => {
+
+                eprintln!("failed to craft '{recipe}', {err}");
+                0
+
+            },
+            Ok(count) => count,
+        }
+
+    }
+
+    /// Crafts the recipe as many times as possible and returns the crafted count.
+    #[wasm_bindgen]
+    pub fn craft_max(&mut self, recipe: &str) -> u32 {
+
+        match self.thing_manager.craft_max(recipe) {
+            Err(err) => {
+
+                eprintln!("failed to craft '{recipe}', {err}");
+                0
+
+            },
+            Ok(count) => count,
+        }
+
+    }
+
+}
+
+// Modifier scaling
+
+#[wasm_bindgen]
+impl Game {
+
+    /// Evaluates a modifier scaling curve for the count.
+    ///
+    /// Uses the same calculation as buildings, so the UI can preview modifier values.
+    #[wasm_bindgen]
+    pub fn evaluate_modifier_scaling(scaling: &str, count: f64) -> f64 {
+
+        match serde_json::from_str::<ModifierScaling>(scaling) {
+            Err(err) => {
+
+                eprintln!("failed to evaluate modifier scaling, failed to parse scaling from str, {err}");
+                0f64
+
+            },
+            Ok(scaling) => scaling.evaluate(count),
+        }
+
+    }
+
+}